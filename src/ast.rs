@@ -1,19 +1,92 @@
+use crate::instruction::BinaryOp;
+
+/// The left-hand side of a `let`, which binds a single name or takes a
+/// tuple apart.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Pattern {
+    Id(String),
+    Tuple(Vec<Pattern>),
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum AstNode {
     Add {
         lhs: Box<AstNode>,
         rhs: Box<AstNode>,
     },
+    Sub {
+        lhs: Box<AstNode>,
+        rhs: Box<AstNode>,
+    },
     Mul {
         lhs: Box<AstNode>,
         rhs: Box<AstNode>,
     },
+    Div {
+        lhs: Box<AstNode>,
+        rhs: Box<AstNode>,
+    },
+    Mod {
+        lhs: Box<AstNode>,
+        rhs: Box<AstNode>,
+    },
+    BitAnd {
+        lhs: Box<AstNode>,
+        rhs: Box<AstNode>,
+    },
+    BitOr {
+        lhs: Box<AstNode>,
+        rhs: Box<AstNode>,
+    },
+    BitXor {
+        lhs: Box<AstNode>,
+        rhs: Box<AstNode>,
+    },
+    ShiftLeft {
+        lhs: Box<AstNode>,
+        rhs: Box<AstNode>,
+    },
+    ShiftRight {
+        lhs: Box<AstNode>,
+        rhs: Box<AstNode>,
+    },
+    Negate {
+        rhs: Box<AstNode>,
+    },
+    BitNot {
+        rhs: Box<AstNode>,
+    },
+    Not {
+        rhs: Box<AstNode>,
+    },
     Number {
-        value: u64,
+        value: i64,
+    },
+    Float {
+        value: f64,
     },
     Boolean {
         value: bool,
     },
+    Str {
+        value: String,
+    },
+    Interpolation {
+        parts: Vec<AstNode>,
+    },
+    Array {
+        elements: Vec<AstNode>,
+    },
+    Tuple {
+        elements: Vec<AstNode>,
+    },
+    Map {
+        entries: Vec<(AstNode, AstNode)>,
+    },
+    Index {
+        target: Box<AstNode>,
+        index: Box<AstNode>,
+    },
     ID {
         value: String,
     },
@@ -25,14 +98,54 @@ pub enum AstNode {
         rhs: Box<AstNode>,
     },
     Declare {
-        id: String,
+        pattern: Pattern,
         rhs: Option<Box<AstNode>>,
     },
+    Const {
+        id: String,
+        rhs: Box<AstNode>,
+    },
+    /// `target op= rhs`, where the target is a name, an index or a field.
+    CompoundAssign {
+        target: Box<AstNode>,
+        op: BinaryOp,
+        rhs: Box<AstNode>,
+    },
+    IndexAssign {
+        target: Box<AstNode>,
+        index: Box<AstNode>,
+        rhs: Box<AstNode>,
+    },
     Function {
         id: String,
         params: Vec<AstNode>,
         block: Vec<AstNode>,
     },
+    Struct {
+        id: String,
+        fields: Vec<AstNode>,
+    },
+    StructLiteral {
+        id: String,
+        fields: Vec<(String, AstNode)>,
+    },
+    Field {
+        target: Box<AstNode>,
+        field: String,
+    },
+    FieldAssign {
+        target: Box<AstNode>,
+        field: String,
+        rhs: Box<AstNode>,
+    },
+    Call {
+        callee: Box<AstNode>,
+        args: Vec<AstNode>,
+    },
+    Lambda {
+        params: Vec<AstNode>,
+        block: Vec<AstNode>,
+    },
     FunctionCall {
         id: String,
         args: Vec<AstNode>,
@@ -56,11 +169,45 @@ pub enum AstNode {
         lhs: Box<AstNode>,
         rhs: Box<AstNode>,
     },
+    GreaterThanOrEqual {
+        lhs: Box<AstNode>,
+        rhs: Box<AstNode>,
+    },
+    LessThanOrEqual {
+        lhs: Box<AstNode>,
+        rhs: Box<AstNode>,
+    },
     Conditional {
         condition: Box<AstNode>,
         block: Vec<AstNode>,
         alternative: Option<Vec<AstNode>>,
     },
+    While {
+        condition: Box<AstNode>,
+        block: Vec<AstNode>,
+    },
+    For {
+        init: Box<AstNode>,
+        condition: Box<AstNode>,
+        step: Option<Box<AstNode>>,
+        block: Vec<AstNode>,
+    },
+    ForIn {
+        id: String,
+        start: Box<AstNode>,
+        end: Box<AstNode>,
+        block: Vec<AstNode>,
+    },
+    Block {
+        block: Vec<AstNode>,
+    },
+    Break,
+    Continue,
+    Ternary {
+        condition: Box<AstNode>,
+        consequent: Box<AstNode>,
+        alternative: Box<AstNode>,
+    },
     LogicalAnd {
         lhs: Box<AstNode>,
         rhs: Box<AstNode>,
@@ -0,0 +1,315 @@
+use std::{
+    cmp::Ordering,
+    fmt::{Display, Formatter, Result},
+    ops::{Add, Mul, Neg, Shl, Shr, Sub},
+};
+
+/// An arbitrary-precision signed integer.
+///
+/// The magnitude is stored as base 2^32 digits, least significant first,
+/// without trailing zero digits. Zero has no digits and is never negative.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BigInt {
+    negative: bool,
+    digits: Vec<u32>,
+}
+
+impl BigInt {
+    fn new(negative: bool, mut digits: Vec<u32>) -> BigInt {
+        while digits.last() == Some(&0) {
+            digits.pop();
+        }
+        BigInt {
+            negative: negative && !digits.is_empty(),
+            digits,
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.digits.is_empty()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn to_i64(&self) -> Option<i64> {
+        if self.digits.len() > 2 {
+            return None;
+        }
+        let magnitude = self
+            .digits
+            .iter()
+            .rev()
+            .fold(0u64, |acc, d| (acc << 32) | *d as u64);
+        if self.negative {
+            0i64.checked_sub_unsigned(magnitude)
+        } else {
+            i64::try_from(magnitude).ok()
+        }
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        let magnitude = self
+            .digits
+            .iter()
+            .rev()
+            .fold(0f64, |acc, d| acc * 4294967296.0 + *d as f64);
+        if self.negative {
+            -magnitude
+        } else {
+            magnitude
+        }
+    }
+
+    /// Truncating division, `None` when dividing by zero.
+    pub fn checked_div(&self, other: &BigInt) -> Option<BigInt> {
+        let (quotient, _) = div_rem(&self.digits, &other.digits)?;
+        Some(BigInt::new(self.negative != other.negative, quotient))
+    }
+
+    /// Remainder of truncating division, so it has the sign of `self`.
+    pub fn checked_rem(&self, other: &BigInt) -> Option<BigInt> {
+        let (_, remainder) = div_rem(&self.digits, &other.digits)?;
+        Some(BigInt::new(self.negative, remainder))
+    }
+}
+
+impl From<i64> for BigInt {
+    fn from(val: i64) -> BigInt {
+        let magnitude = val.unsigned_abs();
+        BigInt::new(val < 0, vec![magnitude as u32, (magnitude >> 32) as u32])
+    }
+}
+
+fn cmp_digits(a: &[u32], b: &[u32]) -> Ordering {
+    a.len()
+        .cmp(&b.len())
+        .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+fn add_digits(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = Vec::with_capacity(a.len().max(b.len()) + 1);
+    let mut carry = 0u64;
+    for i in 0..a.len().max(b.len()) {
+        let sum = *a.get(i).unwrap_or(&0) as u64 + *b.get(i).unwrap_or(&0) as u64 + carry;
+        result.push(sum as u32);
+        carry = sum >> 32;
+    }
+    result.push(carry as u32);
+    result
+}
+
+/// Expects `a >= b`.
+fn sub_digits(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = Vec::with_capacity(a.len());
+    let mut borrow = 0i64;
+    for (i, digit) in a.iter().enumerate() {
+        let mut diff = *digit as i64 - *b.get(i).unwrap_or(&0) as i64 - borrow;
+        borrow = 0;
+        if diff < 0 {
+            diff += 1 << 32;
+            borrow = 1;
+        }
+        result.push(diff as u32);
+    }
+    result
+}
+
+fn mul_digits(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = vec![0u32; a.len() + b.len()];
+    for (i, x) in a.iter().enumerate() {
+        let mut carry = 0u64;
+        for (j, y) in b.iter().enumerate() {
+            let cur = result[i + j] as u64 + *x as u64 * *y as u64 + carry;
+            result[i + j] = cur as u32;
+            carry = cur >> 32;
+        }
+        result[i + b.len()] = carry as u32;
+    }
+    result
+}
+
+fn div_rem_small(a: &[u32], divisor: u32) -> (Vec<u32>, u32) {
+    let mut quotient = vec![0u32; a.len()];
+    let mut remainder = 0u64;
+    for (i, digit) in a.iter().enumerate().rev() {
+        let cur = (remainder << 32) | *digit as u64;
+        quotient[i] = (cur / divisor as u64) as u32;
+        remainder = cur % divisor as u64;
+    }
+    (quotient, remainder as u32)
+}
+
+/// Binary long division of magnitudes, `None` when `b` is zero.
+fn div_rem(a: &[u32], b: &[u32]) -> Option<(Vec<u32>, Vec<u32>)> {
+    match b {
+        [] => None,
+        [divisor] => {
+            let (quotient, remainder) = div_rem_small(a, *divisor);
+            Some((quotient, vec![remainder]))
+        }
+        _ => {
+            let mut quotient = vec![0u32; a.len()];
+            let mut remainder: Vec<u32> = vec![];
+            for bit in (0..a.len() * 32).rev() {
+                // remainder = remainder * 2 + next bit of a
+                let mut carry = (a[bit / 32] >> (bit % 32)) & 1;
+                for digit in remainder.iter_mut() {
+                    let next = *digit >> 31;
+                    *digit = (*digit << 1) | carry;
+                    carry = next;
+                }
+                if carry != 0 {
+                    remainder.push(carry);
+                }
+                if cmp_digits(&remainder, b) != Ordering::Less {
+                    remainder = sub_digits(&remainder, b);
+                    while remainder.last() == Some(&0) {
+                        remainder.pop();
+                    }
+                    quotient[bit / 32] |= 1 << (bit % 32);
+                }
+            }
+            Some((quotient, remainder))
+        }
+    }
+}
+
+impl Add for &BigInt {
+    type Output = BigInt;
+
+    fn add(self, other: &BigInt) -> BigInt {
+        if self.negative == other.negative {
+            return BigInt::new(self.negative, add_digits(&self.digits, &other.digits));
+        }
+        match cmp_digits(&self.digits, &other.digits) {
+            Ordering::Less => BigInt::new(other.negative, sub_digits(&other.digits, &self.digits)),
+            _ => BigInt::new(self.negative, sub_digits(&self.digits, &other.digits)),
+        }
+    }
+}
+
+impl Sub for &BigInt {
+    type Output = BigInt;
+
+    fn sub(self, other: &BigInt) -> BigInt {
+        self + &-other
+    }
+}
+
+impl Mul for &BigInt {
+    type Output = BigInt;
+
+    fn mul(self, other: &BigInt) -> BigInt {
+        BigInt::new(
+            self.negative != other.negative,
+            mul_digits(&self.digits, &other.digits),
+        )
+    }
+}
+
+impl Neg for &BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::new(!self.negative, self.digits.clone())
+    }
+}
+
+impl Shl<u32> for &BigInt {
+    type Output = BigInt;
+
+    fn shl(self, shift: u32) -> BigInt {
+        let (words, bits) = ((shift / 32) as usize, shift % 32);
+        let mut digits = vec![0u32; words];
+        let mut carry = 0u32;
+        for digit in &self.digits {
+            digits.push((digit << bits) | carry);
+            // shifting by 32 would overflow, and nothing carries over anyway
+            carry = if bits == 0 { 0 } else { digit >> (32 - bits) };
+        }
+        digits.push(carry);
+        BigInt::new(self.negative, digits)
+    }
+}
+
+/// Arithmetic shift, so negative values round towards negative infinity like
+/// they do for `i64`.
+impl Shr<u32> for &BigInt {
+    type Output = BigInt;
+
+    fn shr(self, shift: u32) -> BigInt {
+        let (words, bits) = ((shift / 32) as usize, shift % 32);
+        if words >= self.digits.len() {
+            return BigInt::from(if self.negative { -1 } else { 0 });
+        }
+        let mut truncated = self.digits[..words].iter().any(|digit| *digit != 0);
+        let mut digits = Vec::with_capacity(self.digits.len() - words);
+        for (i, digit) in self.digits[words..].iter().enumerate() {
+            let next = self.digits.get(words + i + 1).unwrap_or(&0);
+            digits.push(if bits == 0 {
+                *digit
+            } else {
+                (digit >> bits) | (next << (32 - bits))
+            });
+        }
+        truncated |= bits != 0 && self.digits[words] << (32 - bits) != 0;
+        let magnitude = BigInt::new(false, digits);
+        if self.negative && truncated {
+            -&(&magnitude + &BigInt::from(1))
+        } else if self.negative {
+            -&magnitude
+        } else {
+            magnitude
+        }
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &BigInt) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => cmp_digits(&self.digits, &other.digits),
+            (true, true) => cmp_digits(&other.digits, &self.digits),
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &BigInt) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Display for BigInt {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        if self.is_zero() {
+            return f.write_str("0");
+        }
+        // split into base 10^9 chunks, least significant first
+        let mut chunks = vec![];
+        let mut digits = self.digits.clone();
+        while !digits.is_empty() {
+            let (quotient, chunk) = div_rem_small(&digits, 1_000_000_000);
+            chunks.push(chunk);
+            digits = quotient;
+            while digits.last() == Some(&0) {
+                digits.pop();
+            }
+        }
+        let mut out = String::new();
+        if self.negative {
+            out.push('-');
+        }
+        let mut chunks = chunks.iter().rev();
+        if let Some(first) = chunks.next() {
+            out.push_str(&first.to_string());
+        }
+        for chunk in chunks {
+            out.push_str(&format!("{:09}", chunk));
+        }
+        f.write_str(&out)
+    }
+}
@@ -1,19 +1,26 @@
+use std::collections::HashMap;
+
 use crate::{
-    ast::AstNode,
-    instruction::{BinaryOp, Instruction, StackValue},
+    ast::{AstNode, Pattern},
+    err::InterpError,
+    instruction::{BinaryOp, Instruction, StackValue, UnaryOp},
 };
 
 fn function_call(id: String, args: Vec<AstNode>, prog: &mut Vec<Instruction>) {
+    prog.push(Instruction::FunctionCall {
+        id,
+        args: args_to_bytecode(args),
+    })
+}
+
+fn args_to_bytecode(args: Vec<AstNode>) -> Vec<Vec<Instruction>> {
     let mut args_bytecode = vec![];
     for a in args {
         let bytecode = &mut vec![];
         to_bytecode(a, bytecode);
         args_bytecode.push(bytecode.to_vec());
     }
-    prog.push(Instruction::FunctionCall {
-        id,
-        args: args_bytecode,
-    })
+    args_bytecode
 }
 
 fn function_ast_params_to_vec(params: Vec<AstNode>) -> Vec<String> {
@@ -26,6 +33,43 @@ fn function_ast_params_to_vec(params: Vec<AstNode>) -> Vec<String> {
     return bytecode;
 }
 
+/// Evaluates the target of `target op= rhs` once, keeping a copy of what
+/// the store needs on the stack while the current value is loaded.
+fn compound_assign(target: AstNode, op: BinaryOp, rhs: AstNode, prog: &mut Vec<Instruction>) {
+    match target {
+        AstNode::ID { value } => {
+            prog.push(Instruction::Load { id: value.clone() });
+            to_bytecode(rhs, prog);
+            prog.push(Instruction::BinaryOp { op });
+            prog.push(Instruction::BinaryOp {
+                op: BinaryOp::Assign { name: value },
+            });
+        }
+        AstNode::Index { target, index } => {
+            to_bytecode(*target, prog);
+            to_bytecode(*index, prog);
+            prog.push(Instruction::Dup { count: 2 });
+            prog.push(Instruction::BinaryOp {
+                op: BinaryOp::Index,
+            });
+            to_bytecode(rhs, prog);
+            prog.push(Instruction::BinaryOp { op });
+            prog.push(Instruction::StoreIndex);
+        }
+        AstNode::Field { target, field } => {
+            to_bytecode(*target, prog);
+            prog.push(Instruction::Dup { count: 1 });
+            prog.push(Instruction::LoadField {
+                field: field.clone(),
+            });
+            to_bytecode(rhs, prog);
+            prog.push(Instruction::BinaryOp { op });
+            prog.push(Instruction::StoreField { field });
+        }
+        _ => unreachable!("the parser only allows names, indexes and fields as targets"),
+    }
+}
+
 pub fn block_to_bytecode(block: Vec<AstNode>) -> Vec<Instruction> {
     let bytecodes = &mut vec![];
     for n in block {
@@ -46,46 +90,211 @@ pub fn to_bytecode(ast_node: AstNode, prog: &mut Vec<Instruction>) {
             });
         }
         AstNode::FunctionCall { id, args } => function_call(id, args, prog),
+        AstNode::Call { callee, args } => {
+            to_bytecode(*callee, prog);
+            prog.push(Instruction::Call {
+                args: args_to_bytecode(args),
+            })
+        }
         AstNode::Function { id, params, block } => prog.push(Instruction::FunctionDeclaration {
             name: id,
             block: block_to_bytecode(block),
             params: function_ast_params_to_vec(params),
         }),
+        AstNode::Lambda { params, block } => prog.push(Instruction::Lambda {
+            block: block_to_bytecode(block),
+            params: function_ast_params_to_vec(params),
+        }),
+        AstNode::Struct { id, fields } => prog.push(Instruction::StructDeclaration {
+            name: id,
+            fields: function_ast_params_to_vec(fields),
+        }),
+        AstNode::StructLiteral { id, fields } => {
+            let mut names = vec![];
+            for (name, val) in fields {
+                to_bytecode(val, prog);
+                names.push(name);
+            }
+            prog.push(Instruction::Struct {
+                name: id,
+                fields: names,
+            })
+        }
+        AstNode::Field { target, field } => {
+            to_bytecode(*target, prog);
+            prog.push(Instruction::LoadField { field })
+        }
+        AstNode::FieldAssign { target, field, rhs } => {
+            to_bytecode(*target, prog);
+            to_bytecode(*rhs, prog);
+            prog.push(Instruction::StoreField { field })
+        }
         AstNode::Add { lhs, rhs } => {
             to_bytecode(*lhs, prog);
             to_bytecode(*rhs, prog);
             prog.push(Instruction::BinaryOp { op: BinaryOp::Add })
         }
+        AstNode::Sub { lhs, rhs } => {
+            to_bytecode(*lhs, prog);
+            to_bytecode(*rhs, prog);
+            prog.push(Instruction::BinaryOp { op: BinaryOp::Sub })
+        }
         AstNode::Mul { lhs, rhs } => {
             to_bytecode(*lhs, prog);
             to_bytecode(*rhs, prog);
             prog.push(Instruction::BinaryOp { op: BinaryOp::Mul })
         }
+        AstNode::Div { lhs, rhs } => {
+            to_bytecode(*lhs, prog);
+            to_bytecode(*rhs, prog);
+            prog.push(Instruction::BinaryOp { op: BinaryOp::Div })
+        }
+        AstNode::Mod { lhs, rhs } => {
+            to_bytecode(*lhs, prog);
+            to_bytecode(*rhs, prog);
+            prog.push(Instruction::BinaryOp { op: BinaryOp::Mod })
+        }
+        AstNode::BitAnd { lhs, rhs } => {
+            to_bytecode(*lhs, prog);
+            to_bytecode(*rhs, prog);
+            prog.push(Instruction::BinaryOp {
+                op: BinaryOp::BitAnd,
+            })
+        }
+        AstNode::BitOr { lhs, rhs } => {
+            to_bytecode(*lhs, prog);
+            to_bytecode(*rhs, prog);
+            prog.push(Instruction::BinaryOp {
+                op: BinaryOp::BitOr,
+            })
+        }
+        AstNode::BitXor { lhs, rhs } => {
+            to_bytecode(*lhs, prog);
+            to_bytecode(*rhs, prog);
+            prog.push(Instruction::BinaryOp {
+                op: BinaryOp::BitXor,
+            })
+        }
+        AstNode::ShiftLeft { lhs, rhs } => {
+            to_bytecode(*lhs, prog);
+            to_bytecode(*rhs, prog);
+            prog.push(Instruction::BinaryOp {
+                op: BinaryOp::ShiftLeft,
+            })
+        }
+        AstNode::ShiftRight { lhs, rhs } => {
+            to_bytecode(*lhs, prog);
+            to_bytecode(*rhs, prog);
+            prog.push(Instruction::BinaryOp {
+                op: BinaryOp::ShiftRight,
+            })
+        }
+        AstNode::BitNot { rhs } => {
+            to_bytecode(*rhs, prog);
+            prog.push(Instruction::UnaryOp {
+                op: UnaryOp::BitNot,
+            })
+        }
+        AstNode::Negate { rhs } => {
+            to_bytecode(*rhs, prog);
+            prog.push(Instruction::UnaryOp {
+                op: UnaryOp::Negate,
+            })
+        }
+        AstNode::Not { rhs } => {
+            to_bytecode(*rhs, prog);
+            prog.push(Instruction::UnaryOp { op: UnaryOp::Not })
+        }
         AstNode::Number { value } => prog.push(Instruction::Push {
             value: StackValue::Integer(value),
         }),
+        AstNode::Float { value } => prog.push(Instruction::Push {
+            value: StackValue::Float(value),
+        }),
         AstNode::PrintLn { rhs } => {
             to_bytecode(*rhs, prog);
             prog.push(Instruction::PrintLn {})
         }
-        AstNode::Declare { id, rhs } => {
+        AstNode::Declare { pattern, rhs } => {
             if let Some(val) = rhs {
                 to_bytecode(*val, prog);
             }
             prog.push(Instruction::BinaryOp {
-                op: BinaryOp::Declare { name: id.clone() },
+                op: BinaryOp::Declare { pattern },
             });
         }
+        AstNode::Const { id, rhs } => {
+            to_bytecode(*rhs, prog);
+            prog.push(Instruction::BinaryOp {
+                op: BinaryOp::Const { name: id },
+            });
+        }
+        AstNode::Tuple { elements } => {
+            let len = elements.len();
+            for element in elements {
+                to_bytecode(element, prog);
+            }
+            prog.push(Instruction::Tuple { len })
+        }
         AstNode::Assign { id, rhs } => {
             to_bytecode(*rhs, prog);
             prog.push(Instruction::BinaryOp {
                 op: BinaryOp::Assign { name: id.clone() },
             })
         }
+        AstNode::CompoundAssign { target, op, rhs } => compound_assign(*target, op, *rhs, prog),
+        AstNode::IndexAssign { target, index, rhs } => {
+            to_bytecode(*target, prog);
+            to_bytecode(*index, prog);
+            to_bytecode(*rhs, prog);
+            prog.push(Instruction::StoreIndex)
+        }
+        AstNode::Array { elements } => {
+            let len = elements.len();
+            for element in elements {
+                to_bytecode(element, prog);
+            }
+            prog.push(Instruction::Array { len })
+        }
+        AstNode::Map { entries } => {
+            let len = entries.len();
+            for (key, val) in entries {
+                to_bytecode(key, prog);
+                to_bytecode(val, prog);
+            }
+            prog.push(Instruction::Map { len })
+        }
+        AstNode::Index { target, index } => {
+            to_bytecode(*target, prog);
+            to_bytecode(*index, prog);
+            prog.push(Instruction::BinaryOp {
+                op: BinaryOp::Index,
+            })
+        }
         AstNode::ID { value } => prog.push(Instruction::Load { id: value }),
         AstNode::Boolean { value } => prog.push(Instruction::Push {
             value: StackValue::Boolean(value),
         }),
+        AstNode::Str { value } => prog.push(Instruction::Push {
+            value: StackValue::Str(value),
+        }),
+        AstNode::Interpolation { parts } => {
+            // the leading literal piece is always a string, so every `Add`
+            // that follows concatenates
+            let mut parts = parts.into_iter();
+            if let Some(head) = parts.next() {
+                to_bytecode(head, prog);
+            }
+            for part in parts {
+                if let AstNode::Str { value } = &part {
+                    if value.is_empty() {
+                        continue;
+                    }
+                }
+                to_bytecode(part, prog);
+                prog.push(Instruction::BinaryOp { op: BinaryOp::Add });
+            }
+        }
         AstNode::GreaterThan { lhs, rhs } => {
             to_bytecode(*lhs, prog);
             to_bytecode(*rhs, prog);
@@ -100,6 +309,20 @@ pub fn to_bytecode(ast_node: AstNode, prog: &mut Vec<Instruction>) {
                 op: BinaryOp::LessThan {},
             })
         }
+        AstNode::GreaterThanOrEqual { lhs, rhs } => {
+            to_bytecode(*lhs, prog);
+            to_bytecode(*rhs, prog);
+            prog.push(Instruction::BinaryOp {
+                op: BinaryOp::GreaterThanOrEqual,
+            })
+        }
+        AstNode::LessThanOrEqual { lhs, rhs } => {
+            to_bytecode(*lhs, prog);
+            to_bytecode(*rhs, prog);
+            prog.push(Instruction::BinaryOp {
+                op: BinaryOp::LessThanOrEqual,
+            })
+        }
         AstNode::Empty => { /* DO NOTHING */ }
         AstNode::Conditional {
             condition: ast_condition,
@@ -122,6 +345,77 @@ pub fn to_bytecode(ast_node: AstNode, prog: &mut Vec<Instruction>) {
                 alternative,
             })
         }
+        AstNode::While {
+            condition: ast_condition,
+            block: ast_block,
+        } => {
+            let condition = &mut vec![];
+            to_bytecode(*ast_condition, condition);
+            prog.push(Instruction::While {
+                condition: condition.to_vec(),
+                block: block_to_bytecode(ast_block),
+            })
+        }
+        AstNode::For {
+            init: ast_init,
+            condition: ast_condition,
+            step: ast_step,
+            block: ast_block,
+        } => {
+            let init = &mut vec![];
+            to_bytecode(*ast_init, init);
+            let condition = &mut vec![];
+            to_bytecode(*ast_condition, condition);
+            let step = &mut vec![];
+            if let Some(ast_step) = ast_step {
+                to_bytecode(*ast_step, step);
+            }
+            prog.push(Instruction::For {
+                init: init.to_vec(),
+                condition: condition.to_vec(),
+                step: step.to_vec(),
+                block: block_to_bytecode(ast_block),
+            })
+        }
+        AstNode::ForIn {
+            id,
+            start: ast_start,
+            end: ast_end,
+            block: ast_block,
+        } => {
+            let start = &mut vec![];
+            to_bytecode(*ast_start, start);
+            let end = &mut vec![];
+            to_bytecode(*ast_end, end);
+            prog.push(Instruction::ForIn {
+                id,
+                start: start.to_vec(),
+                end: end.to_vec(),
+                block: block_to_bytecode(ast_block),
+            })
+        }
+        AstNode::Block { block } => prog.push(Instruction::Block {
+            block: block_to_bytecode(block),
+        }),
+        AstNode::Break => prog.push(Instruction::Break),
+        AstNode::Continue => prog.push(Instruction::Continue),
+        AstNode::Ternary {
+            condition: ast_condition,
+            consequent: ast_consequent,
+            alternative: ast_alternative,
+        } => {
+            let condition = &mut vec![];
+            to_bytecode(*ast_condition, condition);
+            let consequent = &mut vec![];
+            to_bytecode(*ast_consequent, consequent);
+            let alternative = &mut vec![];
+            to_bytecode(*ast_alternative, alternative);
+            prog.push(Instruction::Ternary {
+                condition: condition.to_vec(),
+                consequent: consequent.to_vec(),
+                alternative: alternative.to_vec(),
+            })
+        }
         AstNode::Equal { lhs, rhs } => {
             to_bytecode(*lhs, prog);
             to_bytecode(*rhs, prog);
@@ -138,17 +432,264 @@ pub fn to_bytecode(ast_node: AstNode, prog: &mut Vec<Instruction>) {
         }
         AstNode::LogicalAnd { lhs, rhs } => {
             to_bytecode(*lhs, prog);
-            to_bytecode(*rhs, prog);
-            prog.push(Instruction::BinaryOp {
+            let rhs_bytecode = &mut vec![];
+            to_bytecode(*rhs, rhs_bytecode);
+            prog.push(Instruction::ShortCircuit {
                 op: BinaryOp::LogicalAnd,
+                rhs: rhs_bytecode.to_vec(),
             })
         }
         AstNode::LogicalOr { lhs, rhs } => {
             to_bytecode(*lhs, prog);
-            to_bytecode(*rhs, prog);
-            prog.push(Instruction::BinaryOp {
+            let rhs_bytecode = &mut vec![];
+            to_bytecode(*rhs, rhs_bytecode);
+            prog.push(Instruction::ShortCircuit {
                 op: BinaryOp::LogicalOr,
+                rhs: rhs_bytecode.to_vec(),
             })
         }
     }
 }
+
+/// The names declared so far in each enclosing scope, innermost last, and
+/// whether they are constants.
+type Declared = Vec<HashMap<String, bool>>;
+
+/// Rejects assignments to constants that are visible in `ast`, before any of
+/// it runs. Names declared by earlier input are only checked at runtime.
+pub fn check_constants(ast: &[AstNode]) -> Result<(), InterpError> {
+    check_block(ast, &mut vec![HashMap::new()])
+}
+
+fn check_block(block: &[AstNode], declared: &mut Declared) -> Result<(), InterpError> {
+    for node in block {
+        check_node(node, declared)?;
+    }
+    Ok(())
+}
+
+/// Checks `block` in a new scope that starts out with `names`.
+fn check_scope(
+    block: &[AstNode],
+    names: Vec<String>,
+    declared: &mut Declared,
+) -> Result<(), InterpError> {
+    declared.push(names.into_iter().map(|name| (name, false)).collect());
+    let result = check_block(block, declared);
+    declared.pop();
+    result
+}
+
+fn declare(name: &str, constant: bool, declared: &mut Declared) -> Result<(), InterpError> {
+    let scope = declared.last_mut().expect("there is always a scope");
+    if scope.get(name) == Some(&true) {
+        return Err(InterpError::ConstantAssignment(name.to_string()));
+    }
+    scope.insert(name.to_string(), constant);
+    Ok(())
+}
+
+fn declare_pattern(pattern: &Pattern, declared: &mut Declared) -> Result<(), InterpError> {
+    match pattern {
+        Pattern::Id(name) => declare(name, false, declared),
+        Pattern::Tuple(patterns) => {
+            for pattern in patterns {
+                declare_pattern(pattern, declared)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+fn check_assign(name: &str, declared: &Declared) -> Result<(), InterpError> {
+    match declared.iter().rev().find_map(|scope| scope.get(name)) {
+        Some(true) => Err(InterpError::ConstantAssignment(name.to_string())),
+        _ => Ok(()),
+    }
+}
+
+fn check_node(node: &AstNode, declared: &mut Declared) -> Result<(), InterpError> {
+    match node {
+        AstNode::Const { id, rhs } => {
+            check_node(rhs, declared)?;
+            declare(id, true, declared)
+        }
+        AstNode::Declare { pattern, rhs } => {
+            if let Some(rhs) = rhs {
+                check_node(rhs, declared)?;
+            }
+            declare_pattern(pattern, declared)
+        }
+        AstNode::Assign { id, rhs } => {
+            check_node(rhs, declared)?;
+            check_assign(id, declared)
+        }
+        AstNode::Function { id, params, block } => {
+            // like at runtime, only top level functions are constants; a
+            // duplicate declaration is left to fail at runtime
+            let top_level = declared.len() == 1;
+            if let Some(scope) = declared.last_mut() {
+                scope.insert(id.to_string(), top_level);
+            }
+            check_scope(block, function_ast_params_to_vec(params.clone()), declared)
+        }
+        AstNode::Lambda { params, block } => {
+            check_scope(block, function_ast_params_to_vec(params.clone()), declared)
+        }
+        AstNode::Struct { id, .. } => declare(id, false, declared),
+        AstNode::While { condition, block } => {
+            check_node(condition, declared)?;
+            check_scope(block, vec![], declared)
+        }
+        AstNode::For {
+            init,
+            condition,
+            step,
+            block,
+        } => {
+            declared.push(HashMap::new());
+            let result = check_node(init, declared)
+                .and_then(|_| check_node(condition, declared))
+                .and_then(|_| match step {
+                    Some(step) => check_node(step, declared),
+                    None => Ok(()),
+                })
+                .and_then(|_| check_scope(block, vec![], declared));
+            declared.pop();
+            result
+        }
+        AstNode::ForIn {
+            id,
+            start,
+            end,
+            block,
+        } => {
+            check_node(start, declared)?;
+            check_node(end, declared)?;
+            check_scope(block, vec![id.to_string()], declared)
+        }
+        AstNode::Conditional {
+            condition,
+            block,
+            alternative,
+        } => {
+            check_node(condition, declared)?;
+            check_scope(block, vec![], declared)?;
+            match alternative {
+                Some(alternative) => check_scope(alternative, vec![], declared),
+                None => Ok(()),
+            }
+        }
+        AstNode::Block { block } => check_scope(block, vec![], declared),
+        AstNode::Add { lhs, rhs }
+        | AstNode::Sub { lhs, rhs }
+        | AstNode::Mul { lhs, rhs }
+        | AstNode::Div { lhs, rhs }
+        | AstNode::Mod { lhs, rhs }
+        | AstNode::BitAnd { lhs, rhs }
+        | AstNode::BitOr { lhs, rhs }
+        | AstNode::BitXor { lhs, rhs }
+        | AstNode::ShiftLeft { lhs, rhs }
+        | AstNode::ShiftRight { lhs, rhs }
+        | AstNode::GreaterThan { lhs, rhs }
+        | AstNode::Equal { lhs, rhs }
+        | AstNode::NotEqual { lhs, rhs }
+        | AstNode::LessThan { lhs, rhs }
+        | AstNode::GreaterThanOrEqual { lhs, rhs }
+        | AstNode::LessThanOrEqual { lhs, rhs }
+        | AstNode::LogicalAnd { lhs, rhs }
+        | AstNode::LogicalOr { lhs, rhs } => {
+            check_node(lhs, declared)?;
+            check_node(rhs, declared)
+        }
+        AstNode::Negate { rhs } | AstNode::BitNot { rhs } | AstNode::Not { rhs } => {
+            check_node(rhs, declared)
+        }
+        AstNode::PrintLn { rhs } => check_node(rhs, declared),
+        AstNode::Return { block } => check_node(block, declared),
+        AstNode::Interpolation { parts: nodes }
+        | AstNode::Array { elements: nodes }
+        | AstNode::Tuple { elements: nodes }
+        | AstNode::FunctionCall { args: nodes, .. } => check_block(nodes, declared),
+        AstNode::Map { entries } => {
+            for (key, val) in entries {
+                check_node(key, declared)?;
+                check_node(val, declared)?;
+            }
+            Ok(())
+        }
+        AstNode::StructLiteral { fields, .. } => {
+            for (_, val) in fields {
+                check_node(val, declared)?;
+            }
+            Ok(())
+        }
+        AstNode::Index { target, index } => {
+            check_node(target, declared)?;
+            check_node(index, declared)
+        }
+        AstNode::CompoundAssign { target, rhs, .. } => {
+            check_node(rhs, declared)?;
+            match target.as_ref() {
+                AstNode::ID { value } => check_assign(value, declared),
+                target => check_node(target, declared),
+            }
+        }
+        AstNode::IndexAssign { target, index, rhs } => {
+            check_node(target, declared)?;
+            check_node(index, declared)?;
+            check_node(rhs, declared)
+        }
+        AstNode::Field { target, .. } => check_node(target, declared),
+        AstNode::FieldAssign { target, rhs, .. } => {
+            check_node(target, declared)?;
+            check_node(rhs, declared)
+        }
+        AstNode::Call { callee, args } => {
+            check_node(callee, declared)?;
+            check_block(args, declared)
+        }
+        AstNode::Ternary {
+            condition,
+            consequent,
+            alternative,
+        } => {
+            check_node(condition, declared)?;
+            check_node(consequent, declared)?;
+            check_node(alternative, declared)
+        }
+        AstNode::Number { .. }
+        | AstNode::Float { .. }
+        | AstNode::Boolean { .. }
+        | AstNode::Str { .. }
+        | AstNode::ID { .. }
+        | AstNode::Break
+        | AstNode::Continue
+        | AstNode::Empty => Ok(()),
+    }
+}
+
+/// Rejects `break` and `continue` at the top level of `ast`, outside of any
+/// loop. Inside a function they are reported when it is called.
+pub fn check_loop_jumps(ast: &[AstNode]) -> Result<(), InterpError> {
+    for node in ast {
+        match node {
+            AstNode::Break | AstNode::Continue => {
+                return Err(InterpError::EvalError(
+                    "'break' or 'continue' used outside of a loop".to_string(),
+                ))
+            }
+            AstNode::Conditional {
+                block, alternative, ..
+            } => {
+                check_loop_jumps(block)?;
+                if let Some(alternative) = alternative {
+                    check_loop_jumps(alternative)?;
+                }
+            }
+            AstNode::Block { block } => check_loop_jumps(block)?,
+            _ => {}
+        }
+    }
+    Ok(())
+}
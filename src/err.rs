@@ -12,6 +12,11 @@ pub enum InterpError {
     ProgramFileNotFound(String),
     UndefinedFunction(String),
     UndeclaredVariable(String),
+    ConstantAssignment(String),
+    IndexOutOfBounds(i64, usize),
+    KeyNotFound(String),
+    UnknownField(String, String),
+    NotCallable(String),
     EvalError(String),
 }
 
@@ -38,6 +43,21 @@ impl Display for InterpError {
             InterpError::UndeclaredVariable(id) => {
                 f.write_str(format!("Undefined variable '{}'!", id).as_str())
             }
+            InterpError::ConstantAssignment(id) => {
+                f.write_str(format!("Cannot assign to constant '{}'!", id).as_str())
+            }
+            InterpError::IndexOutOfBounds(index, len) => f.write_str(
+                format!("Index {} is out of bounds for length {}!", index, len).as_str(),
+            ),
+            InterpError::KeyNotFound(key) => {
+                f.write_str(format!("Key '{}' not found in map!", key).as_str())
+            }
+            InterpError::UnknownField(name, field) => {
+                f.write_str(format!("Struct '{}' has no field '{}'!", name, field).as_str())
+            }
+            InterpError::NotCallable(val) => {
+                f.write_str(format!("'{}' is not a function!", val).as_str())
+            }
             InterpError::FunctionDuplicate(id) => {
                 f.write_str(format!("Function with the id: '{}' already defined", id).as_str())
             }
@@ -63,6 +83,11 @@ impl Error for InterpError {
             InterpError::UndefinedReference(..) => "VariableNotFound",
             InterpError::ProgramFileNotFound(..) => "ProgramFileNotFound",
             InterpError::UndeclaredVariable(..) => "UndeclaredVariable",
+            InterpError::ConstantAssignment(..) => "ConstantAssignment",
+            InterpError::IndexOutOfBounds(..) => "IndexOutOfBounds",
+            InterpError::KeyNotFound(..) => "KeyNotFound",
+            InterpError::UnknownField(..) => "UnknownField",
+            InterpError::NotCallable(..) => "NotCallable",
             InterpError::FunctionDuplicate(..) => "FunctionDuplicate",
             InterpError::FunctionArgumentsMissmatch(..) => "FunctionArgumentsMissmatch",
         }
@@ -0,0 +1,295 @@
+//! A cycle collector for scopes and heap values.
+//!
+//! Reference counting frees most values as soon as they are unused, but not
+//! cycles: a function is stored in the scope it captures, and an array can
+//! end up containing itself. Every scope store, array, map and struct is
+//! registered here when it is created, and once enough of them have been
+//! allocated a collection frees the ones that can only be reached from each
+//! other.
+//!
+//! Collection uses trial deletion, so the interpreter never has to enumerate
+//! its roots. The references that registered objects hold to each other are
+//! subtracted from their strong counts; whatever is left over is referenced
+//! from outside the heap (the VM stack, a scope that is being evaluated, the
+//! caller) and is live, along with everything it reaches. The rest is
+//! garbage and is cleared, which breaks its cycles and lets `Rc` free it.
+
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    rc::{Rc, Weak},
+};
+
+use crate::{
+    instruction::{MapKey, StackValue, StructValue},
+    scope::{Object, Scope},
+};
+
+pub(crate) type Store = RefCell<HashMap<String, Object>>;
+type Array = RefCell<Vec<StackValue>>;
+type Map = RefCell<HashMap<MapKey, StackValue>>;
+type Struct = RefCell<StructValue>;
+
+/// Objects allocated between collections, unless more than this survived the
+/// last one.
+const MIN_THRESHOLD: usize = 1000;
+
+enum Tracked {
+    Store(Weak<Store>),
+    Array(Weak<Array>),
+    Map(Weak<Map>),
+    Struct(Weak<Struct>),
+}
+
+impl Tracked {
+    fn upgrade(&self) -> Option<Live> {
+        match self {
+            Tracked::Store(obj) => obj.upgrade().map(Live::Store),
+            Tracked::Array(obj) => obj.upgrade().map(Live::Array),
+            Tracked::Map(obj) => obj.upgrade().map(Live::Map),
+            Tracked::Struct(obj) => obj.upgrade().map(Live::Struct),
+        }
+    }
+
+    fn is_alive(&self) -> bool {
+        match self {
+            Tracked::Store(obj) => obj.strong_count() > 0,
+            Tracked::Array(obj) => obj.strong_count() > 0,
+            Tracked::Map(obj) => obj.strong_count() > 0,
+            Tracked::Struct(obj) => obj.strong_count() > 0,
+        }
+    }
+}
+
+enum Live {
+    Store(Rc<Store>),
+    Array(Rc<Array>),
+    Map(Rc<Map>),
+    Struct(Rc<Struct>),
+}
+
+impl Live {
+    fn addr(&self) -> usize {
+        match self {
+            Live::Store(obj) => addr(obj),
+            Live::Array(obj) => addr(obj),
+            Live::Map(obj) => addr(obj),
+            Live::Struct(obj) => addr(obj),
+        }
+    }
+
+    fn strong_count(&self) -> usize {
+        match self {
+            Live::Store(obj) => Rc::strong_count(obj),
+            Live::Array(obj) => Rc::strong_count(obj),
+            Live::Map(obj) => Rc::strong_count(obj),
+            Live::Struct(obj) => Rc::strong_count(obj),
+        }
+    }
+
+    /// Calls `visit` for every reference this object holds, or returns false
+    /// without visiting anything if it is borrowed right now.
+    fn visit_refs(&self, visit: &mut dyn FnMut(usize)) -> bool {
+        match self {
+            Live::Store(obj) => match obj.try_borrow() {
+                Ok(store) => store.values().for_each(|o| visit_object(o, visit)),
+                Err(_) => return false,
+            },
+            Live::Array(obj) => match obj.try_borrow() {
+                Ok(elements) => elements.iter().for_each(|v| visit_value(v, visit)),
+                Err(_) => return false,
+            },
+            Live::Map(obj) => match obj.try_borrow() {
+                Ok(entries) => entries.values().for_each(|v| visit_value(v, visit)),
+                Err(_) => return false,
+            },
+            Live::Struct(obj) => match obj.try_borrow() {
+                Ok(instance) => instance
+                    .fields
+                    .iter()
+                    .for_each(|(_, v)| visit_value(v, visit)),
+                Err(_) => return false,
+            },
+        }
+        true
+    }
+
+    fn clear(&self) {
+        match self {
+            Live::Store(obj) => obj.borrow_mut().clear(),
+            Live::Array(obj) => obj.borrow_mut().clear(),
+            Live::Map(obj) => obj.borrow_mut().clear(),
+            Live::Struct(obj) => obj.borrow_mut().fields.clear(),
+        }
+    }
+}
+
+fn addr<T>(obj: &Rc<T>) -> usize {
+    Rc::as_ptr(obj) as usize
+}
+
+fn visit_object(obj: &Object, visit: &mut dyn FnMut(usize)) {
+    match obj {
+        Object::Function { scope, .. } => visit_scope(scope, visit),
+        Object::Value { value } => visit_value(value, visit),
+        Object::Struct { .. } => {}
+    }
+}
+
+fn visit_scope(scope: &Scope, visit: &mut dyn FnMut(usize)) {
+    // a scope holds its own store and, through its outer scopes, every
+    // enclosing one
+    scope.for_each_store(&mut |store| visit(addr(store)));
+}
+
+fn visit_value(val: &StackValue, visit: &mut dyn FnMut(usize)) {
+    match val {
+        StackValue::Array(obj) => visit(addr(obj)),
+        StackValue::Map(obj) => visit(addr(obj)),
+        StackValue::Struct(obj) => visit(addr(obj)),
+        StackValue::Tuple(elements) => elements.iter().for_each(|v| visit_value(v, visit)),
+        StackValue::Function(_, obj) => visit_object(obj, visit),
+        StackValue::Integer(_)
+        | StackValue::Float(_)
+        | StackValue::BigInt(_)
+        | StackValue::Boolean(_)
+        | StackValue::Str(_) => {}
+    }
+}
+
+struct Heap {
+    objects: Vec<Tracked>,
+    allocated: usize,
+    threshold: usize,
+}
+
+thread_local! {
+    static HEAP: RefCell<Heap> = const {
+        RefCell::new(Heap {
+            objects: vec![],
+            allocated: 0,
+            threshold: MIN_THRESHOLD,
+        })
+    };
+}
+
+fn track(obj: Tracked) {
+    HEAP.with(|heap| {
+        let mut heap = heap.borrow_mut();
+        heap.objects.push(obj);
+        heap.allocated += 1;
+    });
+    maybe_collect();
+}
+
+/// Allocates an empty scope store.
+pub(crate) fn store() -> Rc<Store> {
+    let store = Rc::new(RefCell::new(HashMap::new()));
+    track(Tracked::Store(Rc::downgrade(&store)));
+    store
+}
+
+pub fn array(elements: Vec<StackValue>) -> StackValue {
+    let array = Rc::new(RefCell::new(elements));
+    track(Tracked::Array(Rc::downgrade(&array)));
+    StackValue::Array(array)
+}
+
+pub fn map(entries: HashMap<MapKey, StackValue>) -> StackValue {
+    let map = Rc::new(RefCell::new(entries));
+    track(Tracked::Map(Rc::downgrade(&map)));
+    StackValue::Map(map)
+}
+
+pub fn structure(instance: StructValue) -> StackValue {
+    let instance = Rc::new(RefCell::new(instance));
+    track(Tracked::Struct(Rc::downgrade(&instance)));
+    StackValue::Struct(instance)
+}
+
+/// Collects if enough objects were allocated since the last collection. The
+/// object that was just allocated is still held by its caller, so it is live.
+fn maybe_collect() {
+    let due = HEAP.with(|heap| {
+        let heap = heap.borrow();
+        heap.allocated >= heap.threshold
+    });
+    if due {
+        collect();
+    }
+}
+
+/// Frees every object that is only reachable from other registered objects
+/// and returns how many there were.
+pub fn collect() -> usize {
+    let objects: Vec<Live> = HEAP.with(|heap| {
+        let mut heap = heap.borrow_mut();
+        heap.objects.retain(Tracked::is_alive);
+        heap.objects.iter().filter_map(Tracked::upgrade).collect()
+    });
+    let index: HashMap<usize, usize> = objects
+        .iter()
+        .enumerate()
+        .map(|(i, obj)| (obj.addr(), i))
+        .collect();
+
+    // references that are not held by registered objects, less our own
+    let mut external: Vec<usize> = objects.iter().map(|obj| obj.strong_count() - 1).collect();
+    let mut borrowed = vec![false; objects.len()];
+    for (i, obj) in objects.iter().enumerate() {
+        borrowed[i] = !obj.visit_refs(&mut |ptr| {
+            if let Some(&j) = index.get(&ptr) {
+                external[j] = external[j].saturating_sub(1);
+            }
+        });
+    }
+
+    // a borrowed object is in use; its references were not subtracted, so
+    // whatever it holds is treated as externally referenced too
+    let mut reachable = vec![false; objects.len()];
+    let mut pending: Vec<usize> = (0..objects.len())
+        .filter(|&i| external[i] > 0 || borrowed[i])
+        .collect();
+    while let Some(i) = pending.pop() {
+        if reachable[i] {
+            continue;
+        }
+        reachable[i] = true;
+        objects[i].visit_refs(&mut |ptr| {
+            if let Some(&j) = index.get(&ptr) {
+                if !reachable[j] {
+                    pending.push(j);
+                }
+            }
+        });
+    }
+
+    let mut freed = 0;
+    for (obj, _) in objects.iter().zip(&reachable).filter(|(_, r)| !**r) {
+        obj.clear();
+        freed += 1;
+    }
+    let survivors = objects.len() - freed;
+    drop(objects);
+
+    HEAP.with(|heap| {
+        let mut heap = heap.borrow_mut();
+        heap.objects.retain(Tracked::is_alive);
+        heap.allocated = 0;
+        // keep collections proportional to the heap size, so a large live
+        // heap is not traced over and over
+        heap.threshold = MIN_THRESHOLD.max(survivors);
+    });
+    freed
+}
+
+/// The number of registered objects that have not been freed yet.
+pub fn live_objects() -> usize {
+    HEAP.with(|heap| {
+        heap.borrow()
+            .objects
+            .iter()
+            .filter(|obj| obj.is_alive())
+            .count()
+    })
+}
@@ -1,20 +1,104 @@
 use std::{
+    cell::RefCell,
+    collections::HashMap,
     fmt::{Display, Error, Formatter},
     mem::discriminant,
+    rc::Rc,
 };
 
-use crate::{err::InterpError, scope::Object};
+use crate::{ast::Pattern, bigint::BigInt, err::InterpError, scope::Object};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum StackValue {
-    Integer(u64),
+    Integer(i64),
+    Float(f64),
+    BigInt(BigInt),
     Function(String, Box<Object>),
     Boolean(bool),
+    Str(String),
+    Tuple(Vec<StackValue>),
+    /// Arrays are shared: copies of the value refer to the same elements.
+    Array(Rc<RefCell<Vec<StackValue>>>),
+    /// Maps are shared in the same way as arrays.
+    Map(Rc<RefCell<HashMap<MapKey, StackValue>>>),
+    /// Struct instances are shared in the same way as arrays.
+    Struct(Rc<RefCell<StructValue>>),
+}
+
+/// An instance of a declared struct, with its fields in declaration order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructValue {
+    pub name: String,
+    pub fields: Vec<(String, StackValue)>,
+}
+
+impl StructValue {
+    pub fn get(&self, field: &str) -> Result<StackValue, InterpError> {
+        match self.fields.iter().find(|(name, _)| name == field) {
+            Some((_, val)) => Ok(val.clone()),
+            None => Err(InterpError::UnknownField(
+                self.name.to_string(),
+                field.to_string(),
+            )),
+        }
+    }
+
+    pub fn set(&mut self, field: &str, val: StackValue) -> Result<(), InterpError> {
+        match self.fields.iter_mut().find(|(name, _)| name == field) {
+            Some((_, old)) => {
+                *old = val;
+                Ok(())
+            }
+            None => Err(InterpError::UnknownField(
+                self.name.to_string(),
+                field.to_string(),
+            )),
+        }
+    }
+}
+
+/// The values that can be used as map keys.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum MapKey {
+    Integer(i64),
+    Boolean(bool),
+    Str(String),
+}
+
+impl MapKey {
+    pub fn from_value(val: &StackValue) -> Result<MapKey, InterpError> {
+        match val {
+            StackValue::Integer(v) => Ok(MapKey::Integer(*v)),
+            StackValue::Boolean(v) => Ok(MapKey::Boolean(*v)),
+            StackValue::Str(v) => Ok(MapKey::Str(v.clone())),
+            val => Err(InterpError::EvalError(format!(
+                "{} cannot be used as a map key",
+                val
+            ))),
+        }
+    }
+
+    pub fn to_value(&self) -> StackValue {
+        match self {
+            MapKey::Integer(v) => StackValue::Integer(*v),
+            MapKey::Boolean(v) => StackValue::Boolean(*v),
+            MapKey::Str(v) => StackValue::Str(v.clone()),
+        }
+    }
+}
+
+impl Display for MapKey {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        self.to_value().fmt(f)
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum JumpInstruction {
-    Return,
+    /// Whether the returned value was pushed onto the stack.
+    Return(bool),
+    Break,
+    Continue,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -24,7 +108,7 @@ pub enum EvalResult {
 }
 
 impl StackValue {
-    pub fn as_int(&self) -> Result<u64, InterpError> {
+    pub fn as_int(&self) -> Result<i64, InterpError> {
         match self {
             StackValue::Integer(v) => Ok(*v),
             a => Err(InterpError::EvalError(
@@ -41,19 +125,132 @@ impl StackValue {
         }
     }
 
+    pub fn is_numeric(&self) -> bool {
+        matches!(
+            self,
+            StackValue::Integer(..) | StackValue::BigInt(..) | StackValue::Float(..)
+        )
+    }
+
     pub fn is_same_type(&self, other: &Self) -> bool {
         discriminant(self) == discriminant(other)
     }
 }
 
+impl StackValue {
+    /// Formats the value. `parents` holds the containers that are being
+    /// formatted around it, so one that contains itself is printed as a
+    /// placeholder instead of forever.
+    /// Formats a value inside a container, where strings are quoted so they
+    /// can be told apart from the other values.
+    fn format_element(&self, parents: &mut Vec<usize>) -> String {
+        match self {
+            StackValue::Str(val) => quote(val),
+            val => val.format(parents),
+        }
+    }
+
+    fn format(&self, parents: &mut Vec<usize>) -> String {
+        match self {
+            StackValue::Integer(val) => format!("{}", val),
+            StackValue::BigInt(val) => format!("{}", val),
+            // `{:?}` always keeps a `.0` or an exponent, so floats print as float literals
+            StackValue::Float(val) => format!("{:?}", val),
+            StackValue::Boolean(val) => format!("{}", val),
+            StackValue::Str(val) => val.to_string(),
+            StackValue::Function(id, ..) => format!("function {}", id),
+            StackValue::Array(elements) => {
+                let ptr = Rc::as_ptr(elements) as usize;
+                if parents.contains(&ptr) {
+                    return "[...]".to_string();
+                }
+                parents.push(ptr);
+                let elements: Vec<String> = elements
+                    .borrow()
+                    .iter()
+                    .map(|e| e.format_element(parents))
+                    .collect();
+                parents.pop();
+                format!("[{}]", elements.join(", "))
+            }
+            StackValue::Tuple(elements) => {
+                let elements: Vec<String> =
+                    elements.iter().map(|e| e.format_element(parents)).collect();
+                match elements.as_slice() {
+                    [element] => format!("({},)", element),
+                    _ => format!("({})", elements.join(", ")),
+                }
+            }
+            StackValue::Map(map) => {
+                let ptr = Rc::as_ptr(map) as usize;
+                if parents.contains(&ptr) {
+                    return "{...}".to_string();
+                }
+                parents.push(ptr);
+                // sorted by key, so the output does not depend on hashing
+                let entries = map.borrow();
+                let mut entries: Vec<_> = entries.iter().collect();
+                entries.sort_by_key(|(key, _)| *key);
+                let entries: Vec<String> = entries
+                    .iter()
+                    .map(|(key, val)| {
+                        format!(
+                            "{}: {}",
+                            key.to_value().format_element(parents),
+                            val.format_element(parents)
+                        )
+                    })
+                    .collect();
+                parents.pop();
+                format!("{{{}}}", entries.join(", "))
+            }
+            StackValue::Struct(value) => {
+                let ptr = Rc::as_ptr(value) as usize;
+                let instance = value.borrow();
+                if parents.contains(&ptr) {
+                    return format!("{} {{...}}", instance.name);
+                }
+                parents.push(ptr);
+                let fields: Vec<String> = instance
+                    .fields
+                    .iter()
+                    .map(|(name, val)| format!("{}: {}", name, val.format_element(parents)))
+                    .collect();
+                parents.pop();
+                if fields.is_empty() {
+                    format!("{} {{}}", instance.name)
+                } else {
+                    format!("{} {{ {} }}", instance.name, fields.join(", "))
+                }
+            }
+        }
+    }
+}
+
+/// Writes a string as a string literal, escaping what the lexer unescapes.
+fn quote(val: &str) -> String {
+    let mut quoted = String::from("\"");
+    let mut chars = val.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            '\r' => quoted.push_str("\\r"),
+            '\0' => quoted.push_str("\\0"),
+            '\\' => quoted.push_str("\\\\"),
+            '"' => quoted.push_str("\\\""),
+            // only `${` would start an interpolation
+            '$' if chars.peek() == Some(&'{') => quoted.push_str("\\$"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
 impl Display for StackValue {
     fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
-        let a = match self {
-            StackValue::Integer(val) => f.write_str(format!("{}", val).as_str()),
-            StackValue::Boolean(val) => f.write_str(format!("{}", val).as_str()),
-            StackValue::Function(id, ..) => f.write_str(format!("function {}", id).as_str()),
-        };
-        return a;
+        f.write_str(&self.format(&mut vec![]))
     }
 }
 
@@ -61,14 +258,26 @@ impl Display for StackValue {
 pub enum BinaryOp {
     LessThan,
     GreaterThan,
+    LessThanOrEqual,
+    GreaterThanOrEqual,
     Add,
+    Sub,
     Mul,
+    Div,
+    Mod,
+    BitAnd,
+    BitOr,
+    BitXor,
+    ShiftLeft,
+    ShiftRight,
+    Index,
     Equal,
     NotEqual,
     LogicalAnd,
     LogicalOr,
     Assign { name: String },
-    Declare { name: String },
+    Declare { pattern: Pattern },
+    Const { name: String },
 }
 
 impl Display for BinaryOp {
@@ -76,14 +285,43 @@ impl Display for BinaryOp {
         match self {
             BinaryOp::LessThan => f.write_str("LessThan"),
             BinaryOp::GreaterThan => f.write_str("GreaterThan"),
+            BinaryOp::LessThanOrEqual => f.write_str("LessThanOrEqual"),
+            BinaryOp::GreaterThanOrEqual => f.write_str("GreaterThanOrEqual"),
             BinaryOp::Add => f.write_str("Add"),
+            BinaryOp::Sub => f.write_str("Sub"),
             BinaryOp::Mul => f.write_str("Mul"),
+            BinaryOp::Div => f.write_str("Div"),
+            BinaryOp::Mod => f.write_str("Mod"),
+            BinaryOp::BitAnd => f.write_str("BitAnd"),
+            BinaryOp::BitOr => f.write_str("BitOr"),
+            BinaryOp::BitXor => f.write_str("BitXor"),
+            BinaryOp::ShiftLeft => f.write_str("ShiftLeft"),
+            BinaryOp::ShiftRight => f.write_str("ShiftRight"),
+            BinaryOp::Index => f.write_str("Index"),
             BinaryOp::Assign { .. } => f.write_str("Assign"),
             BinaryOp::Equal => f.write_str("Equal"),
             BinaryOp::NotEqual => f.write_str("NotEqual"),
             BinaryOp::LogicalAnd => f.write_str("LogicalAnd"),
             BinaryOp::LogicalOr => f.write_str("LogicalOr"),
             BinaryOp::Declare { .. } => f.write_str("Declare"),
+            BinaryOp::Const { .. } => f.write_str("Const"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum UnaryOp {
+    Not,
+    Negate,
+    BitNot,
+}
+
+impl Display for UnaryOp {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        match self {
+            UnaryOp::Not => f.write_str("Not"),
+            UnaryOp::Negate => f.write_str("Negate"),
+            UnaryOp::BitNot => f.write_str("BitNot"),
         }
     }
 }
@@ -93,13 +331,41 @@ pub enum Instruction {
     BinaryOp {
         op: BinaryOp,
     },
+    UnaryOp {
+        op: UnaryOp,
+    },
+    /// Pops a boolean lhs and evaluates `rhs` only when the lhs does not
+    /// already decide the result of the logical `op`.
+    ShortCircuit {
+        op: BinaryOp,
+        rhs: Vec<Instruction>,
+    },
     Push {
         value: StackValue,
     },
     PrintLn,
+    /// Pops `len` values and pushes them as a new array, in push order.
+    Array {
+        len: usize,
+    },
+    /// Pops a value, an index and an array, stores the value at that index
+    /// and pushes the value back.
+    StoreIndex,
+    /// Pops `len` values and pushes them as a new tuple, in push order.
+    Tuple {
+        len: usize,
+    },
+    /// Pops `len` key and value pairs and pushes them as a new map.
+    Map {
+        len: usize,
+    },
     Load {
         id: String,
     },
+    /// Pushes copies of the top `count` values, in the same order.
+    Dup {
+        count: usize,
+    },
     Return {
         block: Vec<Instruction>,
     },
@@ -108,28 +374,105 @@ pub enum Instruction {
         params: Vec<String>,
         block: Vec<Instruction>,
     },
+    /// Pops the callee pushed before it and calls it with `args`.
+    Call {
+        args: Vec<Vec<Instruction>>,
+    },
+    /// Pushes an anonymous function that captures the current scope.
+    Lambda {
+        params: Vec<String>,
+        block: Vec<Instruction>,
+    },
+    StructDeclaration {
+        name: String,
+        fields: Vec<String>,
+    },
+    /// Pops one value per field, in the order of `fields`, and pushes a new
+    /// instance of the struct `name`.
+    Struct {
+        name: String,
+        fields: Vec<String>,
+    },
+    /// Pops a struct instance and pushes the value of its `field`.
+    LoadField {
+        field: String,
+    },
+    /// Pops a value and a struct instance, stores the value in `field` and
+    /// pushes the value back.
+    StoreField {
+        field: String,
+    },
     FunctionCall {
         id: String,
         args: Vec<Vec<Instruction>>,
     },
+    /// Evaluates `block` in a new scope nested in the current one.
+    Block {
+        block: Vec<Instruction>,
+    },
     Conditional {
         condition: Vec<Instruction>,
         block: Vec<Instruction>,
         alternative: Option<Vec<Instruction>>,
     },
+    While {
+        condition: Vec<Instruction>,
+        block: Vec<Instruction>,
+    },
+    For {
+        init: Vec<Instruction>,
+        condition: Vec<Instruction>,
+        step: Vec<Instruction>,
+        block: Vec<Instruction>,
+    },
+    ForIn {
+        id: String,
+        start: Vec<Instruction>,
+        end: Vec<Instruction>,
+        block: Vec<Instruction>,
+    },
+    Break,
+    Continue,
+    Ternary {
+        condition: Vec<Instruction>,
+        consequent: Vec<Instruction>,
+        alternative: Vec<Instruction>,
+    },
 }
 
 impl Display for Instruction {
     fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
         match self {
             Instruction::Conditional { .. } => f.write_str("Conditional"),
+            Instruction::While { .. } => f.write_str("While"),
+            Instruction::For { .. } => f.write_str("For"),
+            Instruction::ForIn { .. } => f.write_str("ForIn"),
+            Instruction::Break => f.write_str("Break"),
+            Instruction::Continue => f.write_str("Continue"),
+            Instruction::Ternary { .. } => f.write_str("Ternary"),
             Instruction::Push { .. } => f.write_str("Push"),
             Instruction::PrintLn => f.write_str("PrintLn"),
+            Instruction::Array { .. } => f.write_str("Array"),
+            Instruction::StoreIndex => f.write_str("StoreIndex"),
+            Instruction::Map { .. } => f.write_str("Map"),
+            Instruction::Tuple { .. } => f.write_str("Tuple"),
             Instruction::Load { .. } => f.write_str("Load"),
+            Instruction::Dup { .. } => f.write_str("Dup"),
+            Instruction::Block { .. } => f.write_str("Block"),
             Instruction::Return { .. } => f.write_str("Return"),
             Instruction::FunctionDeclaration { .. } => f.write_str("FunctionDeclaration"),
             Instruction::FunctionCall { .. } => f.write_str("FunctionCall"),
+            Instruction::Call { .. } => f.write_str("Call"),
+            Instruction::Lambda { .. } => f.write_str("Lambda"),
+            Instruction::StructDeclaration { .. } => f.write_str("StructDeclaration"),
+            Instruction::Struct { .. } => f.write_str("Struct"),
+            Instruction::LoadField { .. } => f.write_str("LoadField"),
+            Instruction::StoreField { .. } => f.write_str("StoreField"),
             Instruction::BinaryOp { op } => f.write_str(format!("BinaryOp({})", op).as_str()),
+            Instruction::UnaryOp { op } => f.write_str(format!("UnaryOp({})", op).as_str()),
+            Instruction::ShortCircuit { op, .. } => {
+                f.write_str(format!("ShortCircuit({})", op).as_str())
+            }
         }
     }
 }
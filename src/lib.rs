@@ -1,5 +1,8 @@
-use bytecode::block_to_bytecode;
-use instruction::{BinaryOp, EvalResult, Instruction, StackValue};
+use std::{cell::RefCell, cmp::Ordering, collections::HashMap, rc::Rc};
+
+use bigint::BigInt;
+use bytecode::{block_to_bytecode, check_constants, check_loop_jumps};
+use instruction::{BinaryOp, EvalResult, Instruction, MapKey, StackValue, StructValue, UnaryOp};
 use log::debug;
 use lrlex::{lrlex_mod, DefaultLexerTypes};
 use lrpar::{lrpar_mod, LexParseError, NonStreamingLexer};
@@ -9,12 +12,15 @@ lrlex_mod!("yaiwr.l");
 lrpar_mod!("yaiwr.y");
 
 pub mod ast;
+pub mod bigint;
 pub mod bytecode;
 pub mod err;
+pub mod gc;
 pub mod instruction;
+pub mod numeric;
 pub mod scope;
 
-use ast::AstNode;
+use ast::{AstNode, Pattern};
 use err::InterpError;
 
 use crate::{
@@ -22,13 +28,29 @@ use crate::{
     scope::{Function, Object},
 };
 
+/// The name that anonymous functions report in values and errors.
+const LAMBDA_NAME: &str = "anonymous";
+
 pub struct YIWR {
     stack: Vec<StackValue>,
+    bignum: bool,
 }
 
 impl YIWR {
     pub fn new() -> Self {
-        YIWR { stack: vec![] }
+        YIWR {
+            stack: vec![],
+            bignum: false,
+        }
+    }
+
+    /// Integer results that overflow an `i64` become big integers instead of
+    /// failing with a numeric error.
+    pub fn with_bignum() -> Self {
+        YIWR {
+            stack: vec![],
+            bignum: true,
+        }
     }
 
     pub fn stack_pop(&mut self) -> Result<StackValue, InterpError> {
@@ -39,19 +61,41 @@ impl YIWR {
         self.stack.push(val);
     }
 
+    /// Pops the top `n` values in push order, without going below `stack_base`.
+    fn stack_pop_n(&mut self, n: usize, stack_base: usize) -> Result<Vec<StackValue>, InterpError> {
+        let at = self
+            .stack
+            .len()
+            .checked_sub(n)
+            .filter(|at| *at >= stack_base)
+            .ok_or(InterpError::EmptyStack)?;
+        Ok(self.stack.split_off(at))
+    }
+
     pub fn from_str(&self, input: &str) -> Result<Vec<AstNode>, InterpError> {
         let lexer_def = yaiwr_l::lexerdef();
         let lexer = lexer_def.lexer(input);
-        let (ast_exp, errs) = yaiwr_y::parse(&lexer);
+        let unnegated = RefCell::new(vec![]);
+        let (ast_exp, errs) = yaiwr_y::parse(&lexer, &unnegated);
 
         let err_msg = self.get_parse_err(&lexer, errs);
         if err_msg.is_empty() == false {
             return Err(InterpError::ParseError(err_msg));
         }
+        if let Some(span) = unnegated.borrow().first() {
+            return Err(InterpError::ParseError(format!(
+                "{} cannot be represented as a i64",
+                lexer.span_str(*span)
+            )));
+        }
 
         match ast_exp {
             Some(res) => match res {
-                Ok(exp) => Ok(exp),
+                Ok(exp) => {
+                    check_constants(&exp)?;
+                    check_loop_jumps(&exp)?;
+                    Ok(exp)
+                }
                 Err(_) => Err(InterpError::ParseError(err_msg)),
             },
             None => Err(InterpError::ParseError(err_msg)),
@@ -122,18 +166,9 @@ impl YIWR {
     ) -> Result<Option<EvalResult>, InterpError> {
         match scope.get_var(func_name.clone()) {
             Some(Object::Value {
-                value: StackValue::Function(id, f_dec),
-            }) => match *f_dec {
-                Object::Function {
-                    scope,
-                    func: Function { params, block, .. },
-                } => {
-                    let func_scope =
-                        self.construct_function_scope(id.clone(), *scope.clone(), args, &params)?;
-                    return self.eval(&block.clone(), func_scope);
-                }
-                _ => Err(InterpError::UndefinedFunction(id.to_string())),
-            },
+                value: callee @ StackValue::Function(..),
+            }) => self.eval_call(callee, args),
+            Some(Object::Value { value }) => Err(InterpError::NotCallable(value.to_string())),
             Some(Object::Function {
                 func: Function { params, block, .. },
                 scope,
@@ -144,50 +179,283 @@ impl YIWR {
                     args,
                     &params,
                 )?;
-                return self.eval(&block.clone(), func_scope);
+                self.eval_function_body(func_name, &block, func_scope)
             }
+            _ => self.eval_builtin(func_name, args),
+        }
+    }
+
+    /// Calls a function value, wherever it came from.
+    fn eval_call(
+        &mut self,
+        callee: StackValue,
+        args: &Vec<EvalResult>,
+    ) -> Result<Option<EvalResult>, InterpError> {
+        match callee {
+            StackValue::Function(id, f_dec) => match *f_dec {
+                Object::Function {
+                    scope,
+                    func: Function { params, block, .. },
+                } => {
+                    let func_scope =
+                        self.construct_function_scope(id.clone(), *scope.clone(), args, &params)?;
+                    self.eval_function_body(&id, &block, func_scope)
+                }
+                _ => Err(InterpError::UndefinedFunction(id.to_string())),
+            },
+            val => Err(InterpError::NotCallable(val.to_string())),
+        }
+    }
+
+    /// Functions that are available unless a function of the same name is
+    /// declared.
+    fn eval_builtin(
+        &mut self,
+        func_name: &String,
+        args: &Vec<EvalResult>,
+    ) -> Result<Option<EvalResult>, InterpError> {
+        match func_name.as_str() {
+            "keys" => match args.as_slice() {
+                [EvalResult::Value(StackValue::Map(entries))] => {
+                    let mut keys: Vec<MapKey> = entries.borrow().keys().cloned().collect();
+                    keys.sort();
+                    let keys = keys.iter().map(|key| key.to_value()).collect();
+                    Ok(Some(EvalResult::Value(gc::array(keys))))
+                }
+                [EvalResult::Value(val)] => Err(InterpError::EvalError(format!(
+                    "Expected StackValue Map, got {}",
+                    val
+                ))),
+                _ => Err(InterpError::FunctionArgumentsMissmatch(
+                    func_name.to_string(),
+                    1,
+                    args.len(),
+                )),
+            },
             _ => Err(InterpError::UndefinedFunction(func_name.to_string())),
         }
     }
 
+    fn eval_function_body(
+        &mut self,
+        id: &str,
+        block: &Vec<Instruction>,
+        func_scope: Scope,
+    ) -> Result<Option<EvalResult>, InterpError> {
+        let stack_len = self.stack.len();
+        let result = match self.eval(block, func_scope)? {
+            Some(EvalResult::Jump(JumpInstruction::Break | JumpInstruction::Continue)) => {
+                return Err(InterpError::EvalError(format!(
+                    "'break' or 'continue' used outside of a loop in function '{}'",
+                    id
+                )))
+            }
+            // the returned value is left on top of the stack
+            Some(EvalResult::Jump(JumpInstruction::Return(true))) => {
+                Some(EvalResult::Value(self.stack_pop()?))
+            }
+            Some(EvalResult::Jump(JumpInstruction::Return(false))) => None,
+            result => result,
+        };
+        // a call leaves nothing behind but its result
+        self.stack.truncate(stack_len);
+        Ok(result)
+    }
+
+    fn eval_condition(
+        &mut self,
+        condition: &Vec<Instruction>,
+        scope: Scope,
+    ) -> Result<bool, InterpError> {
+        match self.eval(condition, scope)? {
+            Some(EvalResult::Value(val)) => val.as_bool(),
+            _ => Err(InterpError::EvalError(
+                "Condition did not produce a value".to_string(),
+            )),
+        }
+    }
+
+    /// Evaluates a single iteration of a loop body in `scope`, which callers
+    /// create afresh for every iteration. Returns the jump that terminates
+    /// the loop, or `None` if the loop should carry on.
+    fn eval_loop_body(
+        &mut self,
+        block: &Vec<Instruction>,
+        scope: Scope,
+    ) -> Result<Option<JumpInstruction>, InterpError> {
+        let stack_len = self.stack.len();
+        match self.eval(block, scope)? {
+            // the returned value, if any, is on top of the stack and must
+            // survive
+            Some(EvalResult::Jump(JumpInstruction::Return(pushed))) => {
+                let val = if pushed {
+                    Some(self.stack_pop()?)
+                } else {
+                    None
+                };
+                self.stack.truncate(stack_len);
+                if let Some(val) = val {
+                    self.stack_push(val);
+                }
+                Ok(Some(JumpInstruction::Return(pushed)))
+            }
+            result => {
+                // drop values left behind by the statements of the loop body
+                self.stack.truncate(stack_len);
+                match result {
+                    Some(EvalResult::Jump(JumpInstruction::Break)) => {
+                        Ok(Some(JumpInstruction::Break))
+                    }
+                    _ => Ok(None),
+                }
+            }
+        }
+    }
+
+    fn eval_while(
+        &mut self,
+        condition: &Vec<Instruction>,
+        block: &Vec<Instruction>,
+        scope: Scope,
+    ) -> Result<Option<JumpInstruction>, InterpError> {
+        while self.eval_condition(condition, scope.clone())? {
+            let iteration_scope = Scope::from_scope("while".to_string(), scope.clone());
+            match self.eval_loop_body(block, iteration_scope)? {
+                Some(JumpInstruction::Break) => break,
+                Some(jump) => return Ok(Some(jump)),
+                None => {}
+            }
+        }
+        Ok(None)
+    }
+
+    fn eval_for(
+        &mut self,
+        init: &Vec<Instruction>,
+        condition: &Vec<Instruction>,
+        step: &Vec<Instruction>,
+        block: &Vec<Instruction>,
+        scope: Scope,
+    ) -> Result<Option<JumpInstruction>, InterpError> {
+        let loop_scope = Scope::from_scope("for".to_string(), scope);
+        self.eval(init, loop_scope.clone())?;
+        // an empty condition loops until `break` or `return`
+        while condition.is_empty() || self.eval_condition(condition, loop_scope.clone())? {
+            let iteration_scope = Scope::from_scope("for".to_string(), loop_scope.clone());
+            match self.eval_loop_body(block, iteration_scope)? {
+                Some(JumpInstruction::Break) => break,
+                Some(jump) => return Ok(Some(jump)),
+                None => {}
+            }
+            self.eval(step, loop_scope.clone())?;
+        }
+        Ok(None)
+    }
+
+    fn eval_for_in(
+        &mut self,
+        id: &String,
+        start: &Vec<Instruction>,
+        end: &Vec<Instruction>,
+        block: &Vec<Instruction>,
+        scope: Scope,
+    ) -> Result<Option<JumpInstruction>, InterpError> {
+        let start = self.eval_range_bound(start, scope.clone())?;
+        let end = self.eval_range_bound(end, scope.clone())?;
+        for i in start..end {
+            // a fresh binding per iteration, so closures keep the value they saw
+            let iteration_scope = Scope::from_scope("for".to_string(), scope.clone());
+            iteration_scope.dec_var(id.to_string(), StackValue::Integer(i));
+            match self.eval_loop_body(block, iteration_scope)? {
+                Some(JumpInstruction::Break) => break,
+                Some(jump) => return Ok(Some(jump)),
+                None => {}
+            }
+        }
+        Ok(None)
+    }
+
+    fn eval_range_bound(
+        &mut self,
+        bound: &Vec<Instruction>,
+        scope: Scope,
+    ) -> Result<i64, InterpError> {
+        match self.eval(bound, scope)? {
+            Some(EvalResult::Value(val)) => val.as_int(),
+            _ => Err(InterpError::EvalError(
+                "Range bound did not produce a value".to_string(),
+            )),
+        }
+    }
+
     fn eval_binary_op(&mut self, op: &BinaryOp, scope: Scope) -> Result<StackValue, InterpError> {
         match op {
-            BinaryOp::LessThan => {
-                let op1 = self.stack_pop()?.as_int()?;
-                let op2 = self.stack_pop()?.as_int()?;
-                Ok(StackValue::Boolean(op2 < op1))
-            }
-            BinaryOp::GreaterThan => {
-                let op1 = self.stack_pop()?.as_int()?;
-                let op2 = self.stack_pop()?.as_int()?;
-                Ok(StackValue::Boolean(op1 < op2))
+            BinaryOp::LessThan
+            | BinaryOp::GreaterThan
+            | BinaryOp::LessThanOrEqual
+            | BinaryOp::GreaterThanOrEqual => {
+                let op1 = self.stack_pop()?;
+                let op2 = self.stack_pop()?;
+                let ordering = numeric::compare(&op2, &op1)?;
+                Ok(StackValue::Boolean(match op {
+                    BinaryOp::LessThan => ordering == Some(Ordering::Less),
+                    BinaryOp::GreaterThan => ordering == Some(Ordering::Greater),
+                    BinaryOp::LessThanOrEqual => {
+                        matches!(ordering, Some(Ordering::Less | Ordering::Equal))
+                    }
+                    _ => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+                }))
             }
             BinaryOp::Add => {
-                let op1 = self.stack_pop()?.as_int()?;
-                let op2 = self.stack_pop()?.as_int()?;
-                Ok(StackValue::Integer(
-                    op1.checked_add(op2)
-                        .ok_or(InterpError::Numeric("overflowed".to_string()))?,
-                ))
+                let op1 = self.stack_pop()?;
+                let op2 = self.stack_pop()?;
+                match (op2, op1) {
+                    // a string on either side turns `+` into concatenation
+                    (StackValue::Str(lhs), rhs) => Ok(StackValue::Str(format!("{}{}", lhs, rhs))),
+                    (lhs, StackValue::Str(rhs)) => Ok(StackValue::Str(format!("{}{}", lhs, rhs))),
+                    (lhs, rhs) => numeric::arithmetic(op, &lhs, &rhs, self.bignum),
+                }
             }
-            BinaryOp::Mul => {
-                let op1 = self.stack_pop()?.as_int()?;
-                let op2 = self.stack_pop()?.as_int()?;
-                Ok(StackValue::Integer(
-                    op1.checked_mul(op2)
-                        .ok_or(InterpError::Numeric("overflowed".to_string()))?,
-                ))
+            BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div | BinaryOp::Mod => {
+                let op1 = self.stack_pop()?;
+                let op2 = self.stack_pop()?;
+                numeric::arithmetic(op, &op2, &op1, self.bignum)
+            }
+            BinaryOp::BitAnd
+            | BinaryOp::BitOr
+            | BinaryOp::BitXor
+            | BinaryOp::ShiftLeft
+            | BinaryOp::ShiftRight => {
+                let op1 = self.stack_pop()?;
+                let op2 = self.stack_pop()?;
+                numeric::bitwise(op, &op2, &op1, self.bignum)
+            }
+            BinaryOp::Index => {
+                let index = self.stack_pop()?;
+                let target = self.stack_pop()?;
+                index_get(&target, &index)
             }
             BinaryOp::Assign { name, .. } => {
                 let val = self.stack_pop()?;
+                if scope.is_const(name) {
+                    return Err(InterpError::ConstantAssignment(name.to_string()));
+                }
                 match scope.set_var(name.to_string(), val) {
                     Some(val) => Ok(val),
                     None => return Err(InterpError::UndeclaredVariable(name.to_string())),
                 }
             }
-            BinaryOp::Declare { name, .. } => {
+            BinaryOp::Declare { pattern } => {
                 let val = self.stack_pop()?;
-                scope.dec_var(name.to_string(), val.clone());
+                bind_pattern(&scope, pattern, val.clone())?;
+                Ok(val)
+            }
+            BinaryOp::Const { name } => {
+                let val = self.stack_pop()?;
+                if scope.is_local_const(name) {
+                    return Err(InterpError::ConstantAssignment(name.to_string()));
+                }
+                scope.dec_const(name.to_string(), val.clone());
                 Ok(val)
             }
             BinaryOp::Equal => Ok(self.eval_eq()?),
@@ -225,23 +493,119 @@ impl YIWR {
         }
     }
 
+    fn eval_short_circuit(
+        &mut self,
+        op: &BinaryOp,
+        rhs: &Vec<Instruction>,
+        scope: Scope,
+    ) -> Result<StackValue, InterpError> {
+        let lhs = self.stack_pop()?;
+        match (op, &lhs) {
+            (BinaryOp::LogicalAnd, StackValue::Boolean(false))
+            | (BinaryOp::LogicalOr, StackValue::Boolean(true)) => return Ok(lhs),
+            (_, StackValue::Boolean(_)) => {}
+            _ => {
+                return Err(InterpError::EvalError(format!(
+                    "Operand {} cannot be applied to logical {} operation",
+                    lhs, op
+                )))
+            }
+        }
+        self.stack_push(lhs);
+        match self.eval(rhs, scope.clone())? {
+            Some(EvalResult::Value(val)) => self.stack_push(val),
+            _ => return Err(InterpError::EmptyStack),
+        }
+        self.eval_binary_op(op, scope)
+    }
+
+    fn eval_unary_op(&mut self, op: &UnaryOp) -> Result<StackValue, InterpError> {
+        match (op, self.stack_pop()?) {
+            (UnaryOp::Not, StackValue::Boolean(val)) => Ok(StackValue::Boolean(!val)),
+            (UnaryOp::Negate, StackValue::Integer(val)) => match val.checked_neg() {
+                Some(val) => Ok(StackValue::Integer(val)),
+                None if self.bignum => Ok(StackValue::BigInt(-&BigInt::from(val))),
+                None => Err(numeric::overflow_err(false)),
+            },
+            (UnaryOp::Negate, StackValue::BigInt(val)) => Ok(numeric::normalize(-&val)),
+            (UnaryOp::BitNot, StackValue::Integer(val)) => Ok(StackValue::Integer(!val)),
+            (UnaryOp::Negate, StackValue::Float(val)) => Ok(StackValue::Float(-val)),
+            (op, val) => Err(InterpError::EvalError(format!(
+                "Operand {} cannot be applied to unary {} operation",
+                val, op
+            ))),
+        }
+    }
+
+    /// Builds an instance of the struct `name` from a literal's field values,
+    /// which must name every declared field exactly once.
+    fn eval_struct(
+        &self,
+        name: &String,
+        fields: &[String],
+        values: Vec<StackValue>,
+        scope: &Scope,
+    ) -> Result<StackValue, InterpError> {
+        let declared = match scope.get_var(name.clone()) {
+            Some(Object::Struct { fields }) => fields,
+            _ => {
+                return Err(InterpError::EvalError(format!(
+                    "Undefined struct '{}'",
+                    name
+                )))
+            }
+        };
+        let mut instance = StructValue {
+            name: name.to_string(),
+            fields: vec![],
+        };
+        for field in &declared {
+            match fields.iter().filter(|f| *f == field).count() {
+                0 => {
+                    return Err(InterpError::EvalError(format!(
+                        "Missing field '{}' in '{}' literal",
+                        field, name
+                    )))
+                }
+                1 => {}
+                _ => {
+                    return Err(InterpError::EvalError(format!(
+                        "Field '{}' is set more than once in '{}' literal",
+                        field, name
+                    )))
+                }
+            }
+        }
+        for (field, val) in fields.iter().zip(values) {
+            if !declared.contains(field) {
+                return Err(InterpError::UnknownField(
+                    name.to_string(),
+                    field.to_string(),
+                ));
+            }
+            instance.fields.push((field.to_string(), val));
+        }
+        // keep the declaration order, whatever order the literal used
+        instance
+            .fields
+            .sort_by_key(|(field, _)| declared.iter().position(|d| d == field));
+        Ok(gc::structure(instance))
+    }
+
     fn eval_eq(&mut self) -> Result<StackValue, InterpError> {
         let op1 = self.stack_pop()?;
         let op2 = self.stack_pop()?;
-        let stack_value;
-        if op1.is_same_type(&op2) {
-            stack_value = StackValue::Boolean(op1 == op2);
-            self.stack_push(stack_value.clone())
+        if (op1.is_numeric() && op2.is_numeric()) || op1.is_same_type(&op2) {
+            Ok(StackValue::Boolean(values_eq(&op2, &op1)))
         } else {
-            return Err(InterpError::EvalError(
+            Err(InterpError::EvalError(
                 format!(
                     "Operand {} and Operand {} cannot be applied to logical LogicalOr operation",
                     op1, op2
                 )
                 .to_string(),
-            ));
+            ))
         }
-        Ok(stack_value)
     }
 
     pub fn eval_input(input: String) -> Result<Option<EvalResult>, InterpError> {
@@ -257,14 +621,20 @@ impl YIWR {
         instructions: &Vec<Instruction>,
         scope: Scope,
     ) -> Result<Option<EvalResult>, InterpError> {
+        // only values pushed by these instructions belong to the result
+        let stack_base = self.stack.len();
         for instruction in instructions {
             debug!("eval: {:?}. scope: {:?}", instruction, scope.clone());
             match instruction {
                 Instruction::Return { block } => {
-                    if let Some(EvalResult::Value(v)) = self.eval(block, scope.clone())? {
-                        self.stack_push(v);
-                    }
-                    return Ok(Some(EvalResult::Jump(JumpInstruction::Return {})));
+                    let pushed = match self.eval(block, scope.clone())? {
+                        Some(EvalResult::Value(v)) => {
+                            self.stack_push(v);
+                            true
+                        }
+                        _ => false,
+                    };
+                    return Ok(Some(EvalResult::Jump(JumpInstruction::Return(pushed))));
                 }
                 Instruction::FunctionDeclaration {
                     block,
@@ -276,6 +646,45 @@ impl YIWR {
                         scope.dec_func(name.clone(), params.to_vec(), block.to_vec());
                     }
                 },
+                Instruction::Call { args } => {
+                    let callee = self.stack_pop()?;
+                    let args = self.eval_function_args(args, scope.clone())?;
+                    if let Some(EvalResult::Value(x)) = self.eval_call(callee, &args)? {
+                        self.stack_push(x);
+                    }
+                }
+                Instruction::Lambda { params, block } => {
+                    let name = LAMBDA_NAME.to_string();
+                    let func = scope.closure(name.clone(), params.to_vec(), block.to_vec());
+                    self.stack_push(StackValue::Function(name, Box::new(func)));
+                }
+                Instruction::StructDeclaration { name, fields } => {
+                    if scope.get_var(name.clone()).is_some() {
+                        return Err(InterpError::EvalError(format!(
+                            "'{}' is already defined",
+                            name
+                        )));
+                    }
+                    scope.dec_struct(name.clone(), fields.to_vec());
+                }
+                Instruction::Struct { name, fields } => {
+                    let values = self.stack_pop_n(fields.len(), stack_base)?;
+                    let instance = self.eval_struct(name, fields, values, &scope)?;
+                    self.stack_push(instance);
+                }
+                Instruction::LoadField { field } => {
+                    let target = self.stack_pop()?;
+                    let val = as_struct(&target, field)?.borrow().get(field)?;
+                    self.stack_push(val);
+                }
+                Instruction::StoreField { field } => {
+                    let val = self.stack_pop()?;
+                    let target = self.stack_pop()?;
+                    as_struct(&target, field)?
+                        .borrow_mut()
+                        .set(field, val.clone())?;
+                    self.stack_push(val);
+                }
                 Instruction::FunctionCall { id, args } => {
                     let args = self.eval_function_args(&args, scope.clone())?;
                     if let Some(EvalResult::Value(x)) =
@@ -285,15 +694,49 @@ impl YIWR {
                     }
                 }
                 Instruction::Push { value } => self.stack_push(value.clone()),
+                Instruction::Array { len } => {
+                    let elements = self.stack_pop_n(*len, stack_base)?;
+                    self.stack_push(gc::array(elements));
+                }
+                Instruction::Tuple { len } => {
+                    let elements = self.stack_pop_n(*len, stack_base)?;
+                    self.stack_push(StackValue::Tuple(elements));
+                }
+                Instruction::Map { len } => {
+                    let mut entries = HashMap::new();
+                    let mut values = self.stack_pop_n(len * 2, stack_base)?.into_iter();
+                    while let (Some(key), Some(val)) = (values.next(), values.next()) {
+                        entries.insert(MapKey::from_value(&key)?, val);
+                    }
+                    self.stack_push(gc::map(entries));
+                }
+                Instruction::StoreIndex => {
+                    let val = self.stack_pop()?;
+                    let index = self.stack_pop()?;
+                    let target = self.stack_pop()?;
+                    index_set(&target, &index, val.clone())?;
+                    self.stack_push(val);
+                }
                 Instruction::PrintLn => {
                     println!("{}", self.stack_pop()?);
                 }
+                Instruction::Dup { count } => {
+                    let top = self.stack_pop_n(*count, stack_base)?;
+                    self.stack.extend(top.iter().cloned());
+                    self.stack.extend(top);
+                }
                 Instruction::Load { id } => match scope.get_var(id.to_string()) {
                     Some(obj) => match obj.clone() {
                         Object::Value { value } => self.stack_push(value),
                         Object::Function { .. } => {
                             self.stack_push(StackValue::Function(id.to_string(), Box::new(obj)))
                         }
+                        Object::Struct { .. } => {
+                            return Err(InterpError::EvalError(format!(
+                                "Struct '{}' cannot be used as a value",
+                                id
+                            )))
+                        }
                     },
                     _ => return Err(InterpError::UndefinedReference(id.to_string())),
                 },
@@ -301,29 +744,95 @@ impl YIWR {
                     let val = self.eval_binary_op(op, scope.clone())?;
                     self.stack_push(val);
                 }
+                Instruction::UnaryOp { op } => {
+                    let val = self.eval_unary_op(op)?;
+                    self.stack_push(val);
+                }
+                Instruction::ShortCircuit { op, rhs } => {
+                    let val = self.eval_short_circuit(op, rhs, scope.clone())?;
+                    self.stack_push(val);
+                }
                 Instruction::Conditional {
                     condition,
                     block,
                     alternative,
                 } => {
-                    if let Ok(Some(EvalResult::Value(StackValue::Boolean(val)))) =
-                        self.eval(condition, scope.clone())
+                    let mut block_result = None;
+                    let block_scope = Scope::from_scope("if".to_string(), scope.clone());
+                    if self.eval_condition(condition, scope.clone())? {
+                        block_result = self.eval(block, block_scope)?;
+                    } else if let Some(alt) = alternative {
+                        block_result = self.eval(alt, block_scope)?;
+                    }
+                    if let Some(EvalResult::Jump(jump)) = block_result {
+                        return Ok(Some(EvalResult::Jump(jump)));
+                    }
+                }
+                Instruction::Block { block } => {
+                    let block_scope = Scope::from_scope("block".to_string(), scope.clone());
+                    if let Some(EvalResult::Jump(jump)) = self.eval(block, block_scope)? {
+                        return Ok(Some(EvalResult::Jump(jump)));
+                    }
+                }
+                Instruction::While { condition, block } => {
+                    if let Some(jump) = self.eval_while(condition, block, scope.clone())? {
+                        return Ok(Some(EvalResult::Jump(jump)));
+                    }
+                }
+                Instruction::For {
+                    init,
+                    condition,
+                    step,
+                    block,
+                } => {
+                    if let Some(jump) =
+                        self.eval_for(init, condition, step, block, scope.clone())?
                     {
-                        let mut block_result = None;
-                        if val {
-                            block_result = self.eval(block, scope.clone())?;
-                        } else if let Some(alt) = alternative {
-                            block_result = self.eval(alt, scope.clone())?;
+                        return Ok(Some(EvalResult::Jump(jump)));
+                    }
+                }
+                Instruction::ForIn {
+                    id,
+                    start,
+                    end,
+                    block,
+                } => {
+                    if let Some(jump) = self.eval_for_in(id, start, end, block, scope.clone())? {
+                        return Ok(Some(EvalResult::Jump(jump)));
+                    }
+                }
+                Instruction::Break => return Ok(Some(EvalResult::Jump(JumpInstruction::Break))),
+                Instruction::Continue => {
+                    return Ok(Some(EvalResult::Jump(JumpInstruction::Continue)))
+                }
+                Instruction::Ternary {
+                    condition,
+                    consequent,
+                    alternative,
+                } => {
+                    let branch = match self.eval(condition, scope.clone())? {
+                        Some(EvalResult::Value(StackValue::Boolean(true))) => consequent,
+                        Some(EvalResult::Value(StackValue::Boolean(false))) => alternative,
+                        Some(EvalResult::Value(val)) => {
+                            return Err(InterpError::EvalError(format!(
+                                "Ternary condition must be a Boolean, got {}",
+                                val
+                            )))
                         }
-                        if let Some(EvalResult::Jump(JumpInstruction::Return)) = block_result {
-                            break;
+                        _ => {
+                            return Err(InterpError::EvalError(
+                                "Ternary condition did not produce a value".to_string(),
+                            ))
                         }
+                    };
+                    if let Some(EvalResult::Value(val)) = self.eval(branch, scope.clone())? {
+                        self.stack_push(val);
                     }
                 }
             }
         }
         let result;
-        if self.stack.is_empty() {
+        if self.stack.len() <= stack_base {
             result = Ok(None);
         } else {
             let val = self.stack_pop()?;
@@ -333,3 +842,150 @@ impl YIWR {
         return result;
     }
 }
+
+fn index_get(target: &StackValue, index: &StackValue) -> Result<StackValue, InterpError> {
+    match target {
+        StackValue::Array(elements) => {
+            let elements = elements.borrow();
+            let i = array_index(index.as_int()?, elements.len())?;
+            Ok(elements[i].clone())
+        }
+        StackValue::Map(entries) => {
+            let key = MapKey::from_value(index)?;
+            match entries.borrow().get(&key) {
+                Some(val) => Ok(val.clone()),
+                None => Err(InterpError::KeyNotFound(key.to_string())),
+            }
+        }
+        val => Err(InterpError::EvalError(format!("Cannot index into {}", val))),
+    }
+}
+
+/// Array elements must already exist, map entries are inserted when missing.
+fn index_set(target: &StackValue, index: &StackValue, val: StackValue) -> Result<(), InterpError> {
+    match target {
+        StackValue::Array(elements) => {
+            let mut elements = elements.borrow_mut();
+            let i = array_index(index.as_int()?, elements.len())?;
+            elements[i] = val;
+            Ok(())
+        }
+        StackValue::Map(entries) => {
+            entries.borrow_mut().insert(MapKey::from_value(index)?, val);
+            Ok(())
+        }
+        val => Err(InterpError::EvalError(format!("Cannot index into {}", val))),
+    }
+}
+
+/// Declares the names in `pattern`, taking tuples apart element by element.
+fn bind_pattern(scope: &Scope, pattern: &Pattern, val: StackValue) -> Result<(), InterpError> {
+    match (pattern, val) {
+        (Pattern::Id(name), _) if scope.is_local_const(name) => {
+            Err(InterpError::ConstantAssignment(name.to_string()))
+        }
+        (Pattern::Id(name), val) => {
+            scope.dec_var(name.to_string(), val);
+            Ok(())
+        }
+        (Pattern::Tuple(patterns), StackValue::Tuple(elements))
+            if patterns.len() == elements.len() =>
+        {
+            for (pattern, element) in patterns.iter().zip(elements) {
+                bind_pattern(scope, pattern, element)?;
+            }
+            Ok(())
+        }
+        (Pattern::Tuple(patterns), val) => Err(InterpError::EvalError(format!(
+            "Cannot destructure {} into a tuple of {} elements",
+            val,
+            patterns.len()
+        ))),
+    }
+}
+
+fn as_struct(val: &StackValue, field: &str) -> Result<Rc<RefCell<StructValue>>, InterpError> {
+    match val {
+        StackValue::Struct(instance) => Ok(instance.clone()),
+        val => Err(InterpError::EvalError(format!(
+            "Cannot access field '{}' of {}",
+            field, val
+        ))),
+    }
+}
+
+fn array_index(index: i64, len: usize) -> Result<usize, InterpError> {
+    usize::try_from(index)
+        .ok()
+        .filter(|i| *i < len)
+        .ok_or(InterpError::IndexOutOfBounds(index, len))
+}
+
+/// Integers and floats compare by value, arrays, maps and structs compare
+/// their elements. Values of different types are never equal.
+fn values_eq(lhs: &StackValue, rhs: &StackValue) -> bool {
+    values_eq_in(lhs, rhs, &mut vec![])
+}
+
+/// Compares two values. `comparing` holds the pairs of containers that are
+/// being compared around them: reaching one of those pairs again means the
+/// values are cyclic, and nothing so far tells them apart.
+fn values_eq_in(lhs: &StackValue, rhs: &StackValue, comparing: &mut Vec<(usize, usize)>) -> bool {
+    match (lhs, rhs) {
+        (StackValue::Array(l), StackValue::Array(r)) => {
+            let pair = (Rc::as_ptr(l) as usize, Rc::as_ptr(r) as usize);
+            if Rc::ptr_eq(l, r) || comparing.contains(&pair) {
+                return true;
+            }
+            comparing.push(pair);
+            let eq = {
+                let (l, r) = (l.borrow(), r.borrow());
+                l.len() == r.len()
+                    && l.iter()
+                        .zip(r.iter())
+                        .all(|(l, r)| values_eq_in(l, r, comparing))
+            };
+            comparing.pop();
+            eq
+        }
+        (StackValue::Map(l), StackValue::Map(r)) => {
+            let pair = (Rc::as_ptr(l) as usize, Rc::as_ptr(r) as usize);
+            if Rc::ptr_eq(l, r) || comparing.contains(&pair) {
+                return true;
+            }
+            comparing.push(pair);
+            let eq = {
+                let (l, r) = (l.borrow(), r.borrow());
+                l.len() == r.len()
+                    && l.iter()
+                        .all(|(key, l)| r.get(key).is_some_and(|r| values_eq_in(l, r, comparing)))
+            };
+            comparing.pop();
+            eq
+        }
+        (StackValue::Tuple(l), StackValue::Tuple(r)) => {
+            l.len() == r.len() && l.iter().zip(r.iter()).all(|(l, r)| values_eq(l, r))
+        }
+        (StackValue::Struct(l), StackValue::Struct(r)) => {
+            let pair = (Rc::as_ptr(l) as usize, Rc::as_ptr(r) as usize);
+            if Rc::ptr_eq(l, r) || comparing.contains(&pair) {
+                return true;
+            }
+            comparing.push(pair);
+            let eq = {
+                let (l, r) = (l.borrow(), r.borrow());
+                l.name == r.name
+                    && l.fields
+                        .iter()
+                        .zip(r.fields.iter())
+                        .all(|((_, l), (_, r))| values_eq_in(l, r, comparing))
+            };
+            comparing.pop();
+            eq
+        }
+        (l, r) if l.is_numeric() && r.is_numeric() => {
+            matches!(numeric::compare(l, r), Ok(Some(Ordering::Equal)))
+        }
+        (l, r) => l == r,
+    }
+}
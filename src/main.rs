@@ -7,10 +7,15 @@ use yaiwr::{err::InterpError, instruction::EvalResult, scope::Scope, YIWR};
 
 fn main() {
     env_logger::init();
-    let args: Vec<String> = env::args().collect();
+    let mut args: Vec<String> = env::args().collect();
     debug!("cli args {:?}", &args[1..]);
     let scope = Scope::new();
-    let yaiwr = &mut YIWR::new();
+    let yaiwr = &mut if args.iter().any(|arg| arg == "--bignum") {
+        args.retain(|arg| arg != "--bignum");
+        YIWR::with_bignum()
+    } else {
+        YIWR::new()
+    };
     if args.len() > 1 {
         let result;
         if args[1].ends_with(".yaiwr") {
@@ -0,0 +1,217 @@
+use std::cmp::Ordering;
+
+use crate::{
+    bigint::BigInt,
+    err::InterpError,
+    instruction::{BinaryOp, StackValue},
+};
+
+/// The operands of a numeric operation, promoted to a common type.
+/// An integer mixed with a float is promoted to a float, and an integer
+/// mixed with a big integer is promoted to a big integer.
+enum Operands {
+    Integers(i64, i64),
+    BigInts(BigInt, BigInt),
+    Floats(f64, f64),
+}
+
+impl Operands {
+    fn new(lhs: &StackValue, rhs: &StackValue) -> Result<Operands, InterpError> {
+        match (lhs, rhs) {
+            (StackValue::Integer(l), StackValue::Integer(r)) => Ok(Operands::Integers(*l, *r)),
+            (StackValue::Float(l), StackValue::Float(r)) => Ok(Operands::Floats(*l, *r)),
+            (StackValue::Integer(l), StackValue::Float(r)) => Ok(Operands::Floats(*l as f64, *r)),
+            (StackValue::Float(l), StackValue::Integer(r)) => Ok(Operands::Floats(*l, *r as f64)),
+            (StackValue::BigInt(l), StackValue::BigInt(r)) => {
+                Ok(Operands::BigInts(l.clone(), r.clone()))
+            }
+            (StackValue::BigInt(l), StackValue::Integer(r)) => {
+                Ok(Operands::BigInts(l.clone(), BigInt::from(*r)))
+            }
+            (StackValue::Integer(l), StackValue::BigInt(r)) => {
+                Ok(Operands::BigInts(BigInt::from(*l), r.clone()))
+            }
+            (StackValue::BigInt(l), StackValue::Float(r)) => Ok(Operands::Floats(l.to_f64(), *r)),
+            (StackValue::Float(l), StackValue::BigInt(r)) => Ok(Operands::Floats(*l, r.to_f64())),
+            (lhs, rhs) if rhs.is_numeric() => Err(not_numeric(lhs)),
+            (_, rhs) => Err(not_numeric(rhs)),
+        }
+    }
+}
+
+fn not_numeric(val: &StackValue) -> InterpError {
+    InterpError::EvalError(format!("Expected numeric StackValue, got {}!", val))
+}
+
+pub fn overflow_err(negative: bool) -> InterpError {
+    if negative {
+        InterpError::Numeric("underflowed".to_string())
+    } else {
+        InterpError::Numeric("overflowed".to_string())
+    }
+}
+
+/// Turns a big integer back into a plain integer when it fits, so small
+/// values always take the fast path.
+pub fn normalize(val: BigInt) -> StackValue {
+    match val.to_i64() {
+        Some(val) => StackValue::Integer(val),
+        None => StackValue::BigInt(val),
+    }
+}
+
+/// Applies one of `Add`, `Sub`, `Mul`, `Div` or `Mod` to two numbers.
+///
+/// Integer results that do not fit an `i64` are an error, unless `bignum` is
+/// set, in which case they become big integers.
+pub fn arithmetic(
+    op: &BinaryOp,
+    lhs: &StackValue,
+    rhs: &StackValue,
+    bignum: bool,
+) -> Result<StackValue, InterpError> {
+    match Operands::new(lhs, rhs)? {
+        Operands::Integers(l, r) => match integer_arithmetic(op, l, r)? {
+            Some(val) => Ok(StackValue::Integer(val)),
+            None => {
+                let val = big_arithmetic(op, BigInt::from(l), BigInt::from(r))?;
+                if bignum {
+                    Ok(StackValue::BigInt(val))
+                } else {
+                    Err(overflow_err(val.is_negative()))
+                }
+            }
+        },
+        Operands::BigInts(l, r) => Ok(normalize(big_arithmetic(op, l, r)?)),
+        Operands::Floats(l, r) => Ok(StackValue::Float(float_arithmetic(op, l, r)?)),
+    }
+}
+
+/// `None` means the result does not fit an `i64`.
+fn integer_arithmetic(op: &BinaryOp, l: i64, r: i64) -> Result<Option<i64>, InterpError> {
+    match op {
+        BinaryOp::Add => Ok(l.checked_add(r)),
+        BinaryOp::Sub => Ok(l.checked_sub(r)),
+        BinaryOp::Mul => Ok(l.checked_mul(r)),
+        BinaryOp::Div => {
+            if r == 0 {
+                return Err(InterpError::Numeric("division by zero".to_string()));
+            }
+            // i64::MIN / -1 is the only other case that does not fit
+            Ok(l.checked_div(r))
+        }
+        BinaryOp::Mod => {
+            if r == 0 {
+                return Err(InterpError::Numeric("modulo by zero".to_string()));
+            }
+            // i64::MIN % -1 is 0, which wrapping_rem gets right
+            Ok(Some(l.wrapping_rem(r)))
+        }
+        op => Err(not_arithmetic(op)),
+    }
+}
+
+fn big_arithmetic(op: &BinaryOp, l: BigInt, r: BigInt) -> Result<BigInt, InterpError> {
+    match op {
+        BinaryOp::Add => Ok(&l + &r),
+        BinaryOp::Sub => Ok(&l - &r),
+        BinaryOp::Mul => Ok(&l * &r),
+        BinaryOp::Div => l
+            .checked_div(&r)
+            .ok_or_else(|| InterpError::Numeric("division by zero".to_string())),
+        BinaryOp::Mod => l
+            .checked_rem(&r)
+            .ok_or_else(|| InterpError::Numeric("modulo by zero".to_string())),
+        op => Err(not_arithmetic(op)),
+    }
+}
+
+/// Floats follow IEEE 754, so dividing by zero results in an infinity or NaN.
+fn float_arithmetic(op: &BinaryOp, l: f64, r: f64) -> Result<f64, InterpError> {
+    match op {
+        BinaryOp::Add => Ok(l + r),
+        BinaryOp::Sub => Ok(l - r),
+        BinaryOp::Mul => Ok(l * r),
+        BinaryOp::Div => Ok(l / r),
+        BinaryOp::Mod => Ok(l % r),
+        op => Err(not_arithmetic(op)),
+    }
+}
+
+fn not_arithmetic(op: &BinaryOp) -> InterpError {
+    InterpError::EvalError(format!("{} is not an arithmetic operation", op))
+}
+
+/// Applies one of `BitAnd`, `BitOr`, `BitXor`, `ShiftLeft` or `ShiftRight`
+/// to two integers. `ShiftRight` is arithmetic, so it keeps the sign.
+///
+/// With `bignum` set, shifting left never loses bits and big integers can be
+/// shifted as well. The other operations only take plain integers.
+pub fn bitwise(
+    op: &BinaryOp,
+    lhs: &StackValue,
+    rhs: &StackValue,
+    bignum: bool,
+) -> Result<StackValue, InterpError> {
+    if matches!(op, BinaryOp::ShiftLeft | BinaryOp::ShiftRight) {
+        return shift(op, lhs, rhs, bignum);
+    }
+    let l = lhs.as_int()?;
+    let r = rhs.as_int()?;
+    match op {
+        BinaryOp::BitAnd => Ok(StackValue::Integer(l & r)),
+        BinaryOp::BitOr => Ok(StackValue::Integer(l | r)),
+        BinaryOp::BitXor => Ok(StackValue::Integer(l ^ r)),
+        op => Err(InterpError::EvalError(format!(
+            "{} is not a bitwise operation",
+            op
+        ))),
+    }
+}
+
+fn shift(
+    op: &BinaryOp,
+    lhs: &StackValue,
+    rhs: &StackValue,
+    bignum: bool,
+) -> Result<StackValue, InterpError> {
+    let r = rhs.as_int()?;
+    let shift = u32::try_from(r)
+        .ok()
+        .filter(|shift| bignum || *shift < i64::BITS)
+        .ok_or_else(|| {
+            if bignum {
+                InterpError::Numeric(format!("shift amount {} is out of range", r))
+            } else {
+                InterpError::Numeric(format!(
+                    "shift amount {} is out of range for a {}-bit integer",
+                    r,
+                    i64::BITS
+                ))
+            }
+        })?;
+    let l = match lhs {
+        StackValue::BigInt(l) if bignum => l.clone(),
+        StackValue::Integer(l) if bignum => BigInt::from(*l),
+        _ => {
+            let l = lhs.as_int()?;
+            return match op {
+                BinaryOp::ShiftLeft => Ok(StackValue::Integer(l << shift)),
+                _ => Ok(StackValue::Integer(l >> shift)),
+            };
+        }
+    };
+    match op {
+        BinaryOp::ShiftLeft => Ok(normalize(&l << shift)),
+        _ => Ok(normalize(&l >> shift)),
+    }
+}
+
+/// Orders two numbers. `None` means they are unordered, i.e. one is NaN.
+pub fn compare(lhs: &StackValue, rhs: &StackValue) -> Result<Option<Ordering>, InterpError> {
+    match Operands::new(lhs, rhs)? {
+        Operands::Integers(l, r) => Ok(Some(l.cmp(&r))),
+        Operands::BigInts(l, r) => Ok(Some(l.cmp(&r))),
+        Operands::Floats(l, r) => Ok(l.partial_cmp(&r)),
+    }
+}
@@ -1,6 +1,14 @@
-use std::{cell::RefCell, collections::HashMap, rc::Rc};
+use std::{
+    cell::RefCell,
+    collections::HashSet,
+    fmt::{Debug, Formatter, Result},
+    rc::Rc,
+};
 
-use crate::instruction::{Instruction, StackValue};
+use crate::{
+    gc::{self, Store},
+    instruction::{Instruction, StackValue},
+};
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct Function {
@@ -13,31 +21,72 @@ pub struct Function {
 pub enum Object {
     Function { scope: Box<Scope>, func: Function },
     Value { value: StackValue },
+    Struct { fields: Vec<String> },
 }
 
-#[derive(Debug, PartialEq, Clone)]
+/// An environment of variables, functions and structs.
+///
+/// Clones share the same store, so a closure that captures a scope sees
+/// later assignments to it and its own assignments are visible outside.
+/// Every function call, and every loop iteration, gets a new scope whose
+/// outer scope is the one the function was declared in, or the one the loop
+/// runs in.
+#[derive(Clone)]
 pub struct Scope {
-    store: Rc<RefCell<HashMap<String, Object>>>,
+    store: Rc<Store>,
+    /// Names in `store` that cannot be reassigned.
+    constants: Rc<RefCell<HashSet<String>>>,
     outter_scope: Option<Box<Scope>>,
     func_id: String,
 }
 
+/// Scopes are compared by identity: a function is stored in the scope it
+/// captures, so comparing contents would never terminate.
+impl PartialEq for Scope {
+    fn eq(&self, other: &Scope) -> bool {
+        Rc::ptr_eq(&self.store, &other.store)
+    }
+}
+
+/// Only lists the names in each scope, for the same reason as `PartialEq`.
+impl Debug for Scope {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        let mut names: Vec<String> = self.store.borrow().keys().cloned().collect();
+        names.sort();
+        f.debug_struct("Scope")
+            .field("func_id", &self.func_id)
+            .field("names", &names)
+            .field("outter_scope", &self.outter_scope)
+            .finish()
+    }
+}
+
 impl Scope {
     pub fn new() -> Self {
         Scope {
-            store: Rc::new(RefCell::new(HashMap::new())),
+            store: gc::store(),
+            constants: Rc::new(RefCell::new(HashSet::new())),
             outter_scope: None,
             func_id: "root".to_string(),
         }
     }
 
+    /// Calls `f` with the store of this scope and of every outer scope.
+    pub(crate) fn for_each_store(&self, f: &mut dyn FnMut(&Rc<Store>)) {
+        f(&self.store);
+        if let Some(out) = &self.outter_scope {
+            out.for_each_store(f);
+        }
+    }
+
     pub fn get_store_len(&self) -> usize {
         self.store.borrow().len()
     }
 
     pub fn from_scope(func_id: String, outer_scope: Scope) -> Self {
         let scope = Scope {
-            store: Rc::new(RefCell::new(HashMap::new())),
+            store: gc::store(),
+            constants: Rc::new(RefCell::new(HashSet::new())),
             outter_scope: Some(Box::new(outer_scope)),
             func_id,
         };
@@ -50,23 +99,41 @@ impl Scope {
             .insert(id, Object::Value { value: val })
     }
 
+    pub fn dec_const(&self, id: String, val: StackValue) -> Option<Object> {
+        self.constants.borrow_mut().insert(id.clone());
+        self.dec_var(id, val)
+    }
+
+    /// Functions declared at the top level are constants.
     pub fn dec_func(
         &self,
         name: String,
         params: Vec<String>,
         block: Vec<Instruction>,
     ) -> Option<Object> {
-        self.store.borrow_mut().insert(
-            name.clone(),
-            Object::Function {
-                scope: Box::new(self.clone()),
-                func: Function {
-                    name: name.clone(),
-                    params: params,
-                    block: block,
-                },
+        if self.outter_scope.is_none() {
+            self.constants.borrow_mut().insert(name.clone());
+        }
+        let func = self.closure(name.clone(), params, block);
+        self.store.borrow_mut().insert(name, func)
+    }
+
+    /// Creates a function that captures this scope.
+    pub fn closure(&self, name: String, params: Vec<String>, block: Vec<Instruction>) -> Object {
+        Object::Function {
+            scope: Box::new(self.clone()),
+            func: Function {
+                name,
+                params,
+                block,
             },
-        )
+        }
+    }
+
+    pub fn dec_struct(&self, name: String, fields: Vec<String>) -> Option<Object> {
+        self.store
+            .borrow_mut()
+            .insert(name, Object::Struct { fields })
     }
 
     pub fn set_var(&self, id: String, val: StackValue) -> Option<StackValue> {
@@ -85,6 +152,22 @@ impl Scope {
         }
     }
 
+    /// Whether `id` resolves to a constant, looking outwards like `get_var`.
+    pub fn is_const(&self, id: &str) -> bool {
+        if self.store.borrow().contains_key(id) {
+            return self.is_local_const(id);
+        }
+        match &self.outter_scope {
+            Some(out) => out.is_const(id),
+            None => false,
+        }
+    }
+
+    /// Whether `id` is a constant declared in this scope itself.
+    pub fn is_local_const(&self, id: &str) -> bool {
+        self.constants.borrow().contains(id)
+    }
+
     pub fn get_var(&self, id: String) -> Option<Object> {
         let scope = self.store.borrow();
         let var = scope.get(&id.clone());
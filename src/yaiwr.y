@@ -1,5 +1,11 @@
 
 %start StatementList
+// `{}` at the start of a statement is an empty block or an empty map when
+// followed by `;`, `(`, `[` or `-`. Reduce/reduce conflicts are resolved in
+// favour of the earlier production, which makes it a block.
+%expect-rr 4
+// integer literals that only fit in an i64 once they are negated
+%parse-param unnegated: &RefCell<Vec<Span>>
 %%
 
 StatementList -> Result<Vec<AstNode>, ()>:
@@ -23,14 +29,71 @@ SelectionStatement -> Result<AstNode, ()>:
             alternative: Some($10?)
         }) 
     }
+    | 'IF' '(' Expression ')' '{' StatementList '}' 'ELSE' SelectionStatement {
+        // `else if` is an alternative that holds just the next conditional
+        Ok(AstNode::Conditional{
+            condition:  Box::new($3?),
+            block: $6?,
+            alternative: Some(vec![$9?])
+        })
+    }
+    ;
+
+IterationStatement -> Result<AstNode, ()>:
+    'WHILE' '(' Expression ')' '{' StatementList '}' {
+        Ok(AstNode::While{
+            condition: Box::new($3?),
+            block: $6?
+        })
+    }
+    | 'FOR' '(' ExpressionStatement ExpressionStatement ')' '{' StatementList '}' {
+        Ok(AstNode::For{
+            init: Box::new($3?),
+            condition: Box::new($4?),
+            step: None,
+            block: $7?
+        })
+    }
+    | 'FOR' '(' ExpressionStatement ExpressionStatement Expression ')' '{' StatementList '}' {
+        Ok(AstNode::For{
+            init: Box::new($3?),
+            condition: Box::new($4?),
+            step: Some(Box::new($5?)),
+            block: $8?
+        })
+    }
+    | 'FOR' 'IDENTIFIER' 'IN' AdditiveExpression 'DOTDOT' AdditiveExpression '{' StatementList '}' {
+        let id = $2.map_err(|_| ())?;
+        Ok(AstNode::ForIn{
+            id: $lexer.span_str(id.span()).to_string(),
+            start: Box::new($4?),
+            end: Box::new($6?),
+            block: $8?
+        })
+    }
     ;
 
 Statement -> Result<AstNode, ()>:
     ExpressionStatement { $1 }
     | FunctionDefinition { $1 }
+    | StructDefinition { $1 }
     | SelectionStatement { $1 }
+    | IterationStatement { $1 }
     | Builtins { $1 }
     | 'RETURN' Expression ';' { Ok(AstNode::Return{ block: Box::new($2?) }) }
+    | 'BREAK' ';' { Ok(AstNode::Break) }
+    | 'CONTINUE' ';' { Ok(AstNode::Continue) }
+    | Block { $1 }
+    ;
+
+Block -> Result<AstNode, ()>:
+    '{' '}' { Ok(AstNode::Block{ block: vec![] }) }
+    | '{' BlockStatementList '}' { Ok(AstNode::Block{ block: $2? }) }
+    ;
+
+BlockStatementList -> Result<Vec<AstNode>, ()>:
+    Statement { Ok(vec![$1?]) }
+    | BlockStatementList Statement { append($1.map_err(|_| ())?, $2.map_err(|_| ())?) }
     ;
 
 ExpressionStatement -> Result<AstNode, ()>:
@@ -44,14 +107,30 @@ Expression -> Result<AstNode, ()>:
     ;
 
 
-RelationalExpression -> Result<AstNode, ()>: 
+ShiftExpression -> Result<AstNode, ()>:
     AdditiveExpression { $1 }
-    | RelationalExpression 'LESS_THAN' AdditiveExpression {
+    | ShiftExpression 'SHIFT_LEFT' AdditiveExpression {
+        Ok(AstNode::ShiftLeft{ lhs: Box::new($1?), rhs: Box::new($3?) })
+    }
+    | ShiftExpression 'SHIFT_RIGHT' AdditiveExpression {
+        Ok(AstNode::ShiftRight{ lhs: Box::new($1?), rhs: Box::new($3?) })
+    }
+    ;
+
+RelationalExpression -> Result<AstNode, ()>: 
+    ShiftExpression { $1 }
+    | RelationalExpression 'LESS_THAN' ShiftExpression {
         Ok(AstNode::LessThan{ lhs: Box::new($1?), rhs: Box::new($3?) }) 
     }
-    | RelationalExpression 'GREATER_THAN' AdditiveExpression {
+    | RelationalExpression 'GREATER_THAN' ShiftExpression {
         Ok(AstNode::GreaterThan{ lhs: Box::new($1?), rhs: Box::new($3?) })
     }
+    | RelationalExpression 'LESS_THAN_EQ' ShiftExpression {
+        Ok(AstNode::LessThanOrEqual{ lhs: Box::new($1?), rhs: Box::new($3?) })
+    }
+    | RelationalExpression 'GREATER_THAN_EQ' ShiftExpression {
+        Ok(AstNode::GreaterThanOrEqual{ lhs: Box::new($1?), rhs: Box::new($3?) })
+    }
     ;
 
 EqualityExpression -> Result<AstNode, ()>: 
@@ -64,9 +143,30 @@ EqualityExpression -> Result<AstNode, ()>:
     }
     ;
 
-LogincalAndExpression -> Result<AstNode, ()>:
+BitwiseAndExpression -> Result<AstNode, ()>:
     EqualityExpression { $1 }
-    | LogincalAndExpression 'AND' EqualityExpression { 
+    | BitwiseAndExpression 'BIT_AND' EqualityExpression {
+        Ok(AstNode::BitAnd{ lhs: Box::new($1?), rhs: Box::new($3?) })
+    }
+    ;
+
+BitwiseXorExpression -> Result<AstNode, ()>:
+    BitwiseAndExpression { $1 }
+    | BitwiseXorExpression 'BIT_XOR' BitwiseAndExpression {
+        Ok(AstNode::BitXor{ lhs: Box::new($1?), rhs: Box::new($3?) })
+    }
+    ;
+
+BitwiseOrExpression -> Result<AstNode, ()>:
+    BitwiseXorExpression { $1 }
+    | BitwiseOrExpression 'BIT_OR' BitwiseXorExpression {
+        Ok(AstNode::BitOr{ lhs: Box::new($1?), rhs: Box::new($3?) })
+    }
+    ;
+
+LogincalAndExpression -> Result<AstNode, ()>:
+    BitwiseOrExpression { $1 }
+    | LogincalAndExpression 'AND' BitwiseOrExpression { 
         Ok(AstNode::LogicalAnd{ lhs: Box::new($1?), rhs: Box::new($3?) })
     }
     ;
@@ -80,6 +180,13 @@ LogincalOrExpression -> Result<AstNode, ()>:
     
 ConditionalExpression -> Result<AstNode, ()>:
 	LogincalOrExpression { $1 }
+    | LogincalOrExpression 'QUESTION' Expression 'COLON' ConditionalExpression {
+        Ok(AstNode::Ternary{
+            condition: Box::new($1?),
+            consequent: Box::new($3?),
+            alternative: Box::new($5?)
+        })
+    }
 	;
 
 AssignmentExpression -> Result<AstNode, ()>:
@@ -89,17 +196,61 @@ AssignmentExpression -> Result<AstNode, ()>:
             AstNode::ID { value } => {
                 Ok(AstNode::Assign { id: value, rhs: Box::new($3?) })
             },
+            AstNode::Index { target, index } => {
+                Ok(AstNode::IndexAssign { target, index, rhs: Box::new($3?) })
+            },
+            AstNode::Field { target, field } => {
+                Ok(AstNode::FieldAssign { target, field, rhs: Box::new($3?) })
+            },
             _ => Err(())
         }
     }
+    | UnaryExpression CompoundAssignmentOperator AssignmentExpression {
+        compound_assign($1.map_err(|_| ())?, $2?, $3?)
+    }
     | 'LET' UnaryExpression '=' AssignmentExpression {
-        match $2.map_err(|_| ())? {
-            AstNode::ID { value } => {
-                Ok(AstNode::Declare { id: value, rhs: Some(Box::new($4?)) })
-            },
-            _ => Err(())
-        }
+        let pattern = to_pattern($2.map_err(|_| ())?)?;
+        Ok(AstNode::Declare { pattern, rhs: Some(Box::new($4?)) })
     } 
+    | 'CONST' 'IDENTIFIER' '=' AssignmentExpression {
+        let id = $2.map_err(|_| ())?;
+        Ok(AstNode::Const { id: $lexer.span_str(id.span()).to_string(), rhs: Box::new($4?) })
+    }
+    | StructLiteral { $1 }
+    ;
+
+CompoundAssignmentOperator -> Result<BinaryOp, ()>:
+    'ADD_ASSIGN' { Ok(BinaryOp::Add) }
+    | 'SUB_ASSIGN' { Ok(BinaryOp::Sub) }
+    | 'MUL_ASSIGN' { Ok(BinaryOp::Mul) }
+    | 'DIV_ASSIGN' { Ok(BinaryOp::Div) }
+    | 'MOD_ASSIGN' { Ok(BinaryOp::Mod) }
+    ;
+
+// A struct literal is not part of PrimaryExpression so that range bounds,
+// which are followed by the loop body, never take a `{` as a struct literal.
+StructLiteral -> Result<AstNode, ()>:
+    'IDENTIFIER' '{' '}' {
+        let id = $1.map_err(|_| ())?;
+        Ok(AstNode::StructLiteral{ id: $lexer.span_str(id.span()).to_string(), fields: vec![] })
+    }
+    | 'IDENTIFIER' '{' FieldInitList '}' {
+        let id = $1.map_err(|_| ())?;
+        Ok(AstNode::StructLiteral{ id: $lexer.span_str(id.span()).to_string(), fields: $3? })
+    }
+    ;
+
+FieldInitList -> Result<Vec<(String, AstNode)>, ()>:
+    'IDENTIFIER' 'COLON' AssignmentExpression {
+        let field = $1.map_err(|_| ())?;
+        Ok(vec![($lexer.span_str(field.span()).to_string(), $3?)])
+    }
+    | FieldInitList ',' 'IDENTIFIER' 'COLON' AssignmentExpression {
+        let field = $3.map_err(|_| ())?;
+        let mut fields = $1?;
+        fields.push(($lexer.span_str(field.span()).to_string(), $5?));
+        Ok(fields)
+    }
     ;
 
 AdditiveExpression -> Result<AstNode, ()>:
@@ -107,6 +258,9 @@ AdditiveExpression -> Result<AstNode, ()>:
     | AdditiveExpression 'ADD' MultiplicativeExpression { 
         Ok(AstNode::Add{ lhs: Box::new($1?), rhs: Box::new($3?) })
     }
+    | AdditiveExpression 'SUB' MultiplicativeExpression { 
+        Ok(AstNode::Sub{ lhs: Box::new($1?), rhs: Box::new($3?) })
+    }
     ;
 
 MultiplicativeExpression -> Result<AstNode, ()>: 
@@ -114,10 +268,19 @@ MultiplicativeExpression -> Result<AstNode, ()>:
     | MultiplicativeExpression 'MUL' UnaryExpression { 
       Ok(AstNode::Mul{ lhs: Box::new($1?), rhs: Box::new($3?) })
     }
+    | MultiplicativeExpression 'DIV' UnaryExpression { 
+      Ok(AstNode::Div{ lhs: Box::new($1?), rhs: Box::new($3?) })
+    }
+    | MultiplicativeExpression 'MOD' UnaryExpression { 
+      Ok(AstNode::Mod{ lhs: Box::new($1?), rhs: Box::new($3?) })
+    }
     ;
 
 UnaryExpression -> Result<AstNode, ()>: 
     PostfixExpression { $1 }
+    | 'SUB' UnaryExpression { negate($2?, $span, unnegated) }
+    | 'NOT' UnaryExpression { Ok(AstNode::Not{ rhs: Box::new($2?) }) }
+    | 'BIT_NOT' UnaryExpression { Ok(AstNode::BitNot{ rhs: Box::new($2?) }) }
     ;
 
 
@@ -126,15 +289,22 @@ PostfixExpression -> Result<AstNode, ()>:
   | PostfixExpression '(' ')' { 
         match $1.map_err(|_| ())? {
             AstNode::ID { value: id } => Ok(AstNode::FunctionCall{ id, args: vec![] }),
-            _ => Err(())
+            callee => Ok(AstNode::Call{ callee: Box::new(callee), args: vec![] })
         }
     }
   | PostfixExpression '(' ArgumentExpressionList ')' { 
         match $1.map_err(|_| ())? {
         AstNode::ID { value: id } => Ok(AstNode::FunctionCall{ id, args: $3.map_err(|_| ())? }),
-            _ => Err(())
+            callee => Ok(AstNode::Call{ callee: Box::new(callee), args: $3.map_err(|_| ())? })
         }
    }
+  | PostfixExpression 'DOT' 'IDENTIFIER' {
+        let field = $3.map_err(|_| ())?;
+        Ok(AstNode::Field{ target: Box::new($1?), field: $lexer.span_str(field.span()).to_string() })
+    }
+  | PostfixExpression '[' Expression ']' {
+        Ok(AstNode::Index{ target: Box::new($1?), index: Box::new($3?) })
+    }
   ;
     
 ArgumentExpressionList -> Result<Vec<AstNode>, ()>:
@@ -148,13 +318,55 @@ Id -> Result<AstNode, ()>:
 
 PrimaryExpression -> Result<AstNode, ()>:
     Id { $1 }
-    |  '(' Expression ')' { $2 }
+    |  '(' AssignmentExpression ')' { $2 }
+    | '(' AssignmentExpression ',' ')' { Ok(AstNode::Tuple{ elements: vec![$2?] }) }
+    | '(' TupleElements ')' { Ok(AstNode::Tuple{ elements: $2? }) }
+    | Lambda { $1 }
+    | '[' ']' { Ok(AstNode::Array{ elements: vec![] }) }
+    | '[' ArgumentExpressionList ']' { Ok(AstNode::Array{ elements: $2? }) }
+    | '{' '}' { Ok(AstNode::Map{ entries: vec![] }) }
+    | '{' MapEntryList '}' { Ok(AstNode::Map{ entries: $2? }) }
     | Literals { $1 }
     ;
 
+TupleElements -> Result<Vec<AstNode>, ()>:
+    AssignmentExpression ',' AssignmentExpression { Ok(vec![$1?, $3?]) }
+    | TupleElements ',' AssignmentExpression { append($1?, $3?) }
+    ;
+
+MapEntryList -> Result<Vec<(AstNode, AstNode)>, ()>:
+    AssignmentExpression 'COLON' AssignmentExpression { Ok(vec![($1?, $3?)]) }
+    | MapEntryList ',' AssignmentExpression 'COLON' AssignmentExpression {
+        let mut entries = $1?;
+        entries.push(($3?, $5?));
+        Ok(entries)
+    }
+    ;
+
 Literals -> Result<AstNode, ()>:
-    'INTEGER_LITERAL' { parse_int($lexer.span_str(($1.map_err(|_| ())?).span())) }
+    'INTEGER_LITERAL' {
+        let n = $1.map_err(|_| ())?;
+        parse_int($lexer.span_str(n.span()), n.span(), unnegated)
+    }
+    | 'FLOAT_LITERAL' { parse_float($lexer.span_str(($1.map_err(|_| ())?).span())) }
     | 'BOOLEAN_LITERAL' { parse_boolean($lexer.span_str(($1.map_err(|_| ())?).span())) }
+    | 'STRING_LITERAL' { parse_string($lexer.span_str(($1.map_err(|_| ())?).span())) }
+    | InterpolationParts 'STRING_TAIL' {
+        let tail = $lexer.span_str(($2.map_err(|_| ())?).span());
+        let parts = append($1?, parse_string_tail(tail)?)?;
+        Ok(AstNode::Interpolation{ parts })
+    }
+    ;
+
+InterpolationParts -> Result<Vec<AstNode>, ()>:
+    'STRING_HEAD' Expression {
+        let head = $lexer.span_str(($1.map_err(|_| ())?).span());
+        append(vec![parse_string_head(head)?], $2?)
+    }
+    | InterpolationParts 'STRING_MIDDLE' Expression {
+        let middle = $lexer.span_str(($2.map_err(|_| ())?).span());
+        append(append($1?, parse_string_middle(middle)?)?, $3?)
+    }
     ;
 
 ParamList -> Result<Vec<AstNode>, ()>:
@@ -182,27 +394,110 @@ FunctionDefinition -> Result<AstNode, ()>:
      }
     ;
 
+Lambda -> Result<AstNode, ()>:
+    'FUNCTION' '(' ')' '{' StatementList '}' {
+        Ok(AstNode::Lambda{ params: vec![], block: $5? })
+    }
+    | 'FUNCTION' '(' ParamList ')' '{' StatementList '}' {
+        Ok(AstNode::Lambda{ params: $3?, block: $6? })
+    }
+    ;
+
+StructDefinition -> Result<AstNode, ()>:
+    'STRUCT' 'IDENTIFIER' '{' '}' {
+        let id = $2.map_err(|_| ())?;
+        Ok(AstNode::Struct{ id: $lexer.span_str(id.span()).to_string(), fields: vec![] })
+    }
+    | 'STRUCT' 'IDENTIFIER' '{' ParamList '}' {
+        let id = $2.map_err(|_| ())?;
+        Ok(AstNode::Struct{ id: $lexer.span_str(id.span()).to_string(), fields: $4? })
+    }
+    ;
+
 Builtins -> Result<AstNode, ()>:
     'PRINT_LN' '(' Expression ')' { Ok(AstNode::PrintLn{ rhs: Box::new($3?) }) };
 
 %%
-use crate::ast::AstNode;
+use std::cell::RefCell;
+
+use lrpar::Span;
+
+use crate::ast::{AstNode, Pattern};
+use crate::instruction::BinaryOp;
 
 fn append(mut lhs: Vec<AstNode>, rhs: AstNode ) -> Result<Vec<AstNode>, ()>{
     lhs.push(rhs);
     Ok(lhs)
 }
 
-fn parse_int(s: &str) -> Result<AstNode, ()> {
+/// Compound assignment is only allowed where plain assignment is.
+fn compound_assign(target: AstNode, op: BinaryOp, rhs: AstNode) -> Result<AstNode, ()> {
+    match target {
+        AstNode::ID { .. } | AstNode::Index { .. } | AstNode::Field { .. } => {
+            Ok(AstNode::CompoundAssign { target: Box::new(target), op, rhs: Box::new(rhs) })
+        }
+        _ => Err(()),
+    }
+}
+
+/// Turns the left-hand side of a `let` into the pattern it binds.
+fn to_pattern(node: AstNode) -> Result<Pattern, ()> {
+    match node {
+        AstNode::ID { value } => Ok(Pattern::Id(value)),
+        AstNode::Tuple { elements } => {
+            let patterns: Result<Vec<Pattern>, ()> = elements.into_iter().map(to_pattern).collect();
+            Ok(Pattern::Tuple(patterns?))
+        }
+        _ => Err(()),
+    }
+}
+
+/// The magnitude of `i64::MIN` is read as `i64::MIN` itself and recorded in
+/// `unnegated`, until `negate` finds the minus sign in front of it.
+fn parse_int(s: &str, span: Span, unnegated: &RefCell<Vec<Span>>) -> Result<AstNode, ()> {
     match s.parse::<u64>() {
-        Ok(n_val) => Ok(AstNode::Number{ value: n_val }),
+        Ok(n_val) if n_val <= i64::MAX as u64 => Ok(AstNode::Number{ value: n_val as i64 }),
+        Ok(n_val) if n_val == i64::MIN.unsigned_abs() => {
+            unnegated.borrow_mut().push(span);
+            Ok(AstNode::Number{ value: i64::MIN })
+        }
+        _ => {
+            eprintln!("{} cannot be represented as a i64", s);
+            Err(())
+        }
+    }
+}
+
+fn parse_float(s: &str) -> Result<AstNode, ()> {
+    match s.parse::<f64>() {
+        Ok(n_val) => Ok(AstNode::Float{ value: n_val }),
         Err(_) => {
-            eprintln!("{} cannot be represented as a u64", s);
+            eprintln!("{} cannot be represented as a f64", s);
             Err(())
         }
     }
 }
 
+fn negate(node: AstNode, span: Span, unnegated: &RefCell<Vec<Span>>) -> Result<AstNode, ()> {
+    let literal_end = unnegated.borrow().last().map(|lit| lit.end());
+    match node {
+        // the operand is the literal that `parse_int` already read as
+        // `i64::MIN`
+        AstNode::Number { value } if literal_end == Some(span.end()) => {
+            unnegated.borrow_mut().pop();
+            Ok(AstNode::Number { value })
+        }
+        // fold negative literals so that `-1` is a single constant
+        AstNode::Number { value } => match value.checked_neg() {
+            Some(value) => Ok(AstNode::Number { value }),
+            // `-i64::MIN` overflows, which is left to the runtime
+            None => Ok(AstNode::Negate { rhs: Box::new(AstNode::Number { value }) }),
+        },
+        AstNode::Float { value } => Ok(AstNode::Float { value: -value }),
+        rhs => Ok(AstNode::Negate { rhs: Box::new(rhs) }),
+    }
+}
+
 fn parse_boolean(s: &str) -> Result<AstNode, ()> {
     match s.parse::<bool>() {
         Ok(n_val) => Ok(AstNode::Boolean{ value: n_val }),
@@ -212,3 +507,46 @@ fn parse_boolean(s: &str) -> Result<AstNode, ()> {
         }
     }
 }
+
+fn parse_string(s: &str) -> Result<AstNode, ()> {
+    parse_string_part(s, "\"", "\"")
+}
+
+fn parse_string_head(s: &str) -> Result<AstNode, ()> {
+    parse_string_part(s, "\"", "${")
+}
+
+fn parse_string_middle(s: &str) -> Result<AstNode, ()> {
+    parse_string_part(s, "}", "${")
+}
+
+fn parse_string_tail(s: &str) -> Result<AstNode, ()> {
+    parse_string_part(s, "}", "\"")
+}
+
+/// Parses the text of a string literal, or of a piece of one surrounding an
+/// `${...}` interpolation, between the given delimiters.
+fn parse_string_part(s: &str, prefix: &str, suffix: &str) -> Result<AstNode, ()> {
+    let mut chars = s[prefix.len()..s.len() - suffix.len()].chars();
+    let mut value = String::new();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            value.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => value.push('\n'),
+            Some('t') => value.push('\t'),
+            Some('r') => value.push('\r'),
+            Some('0') => value.push('\0'),
+            Some('\\') => value.push('\\'),
+            Some('"') => value.push('"'),
+            Some('$') => value.push('$'),
+            other => {
+                eprintln!("{} contains an invalid escape sequence \\{}", s, other.unwrap_or(' '));
+                return Err(());
+            }
+        }
+    }
+    Ok(AstNode::Str { value })
+}
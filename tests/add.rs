@@ -32,14 +32,14 @@ mod tests {
 
     #[test]
     #[should_panic(expected = "overflowed")]
-    fn add_overflow_max_u64() {
-        let input = format!("{}+{};", u64::MAX, 1);
+    fn add_overflow_max_i64() {
+        let input = format!("{}+{};", i64::MAX, 1);
         YIWR::eval_input(input).unwrap();
     }
 
     #[test]
     fn add_no_overflow() {
-        let input = format!("{}+{};", u64::MAX - 1, 1);
+        let input = format!("{}+{};", i64::MAX - 1, 1);
         YIWR::eval_input(input).unwrap();
     }
 }
@@ -0,0 +1,187 @@
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use yaiwr::{
+        err::InterpError,
+        instruction::{BinaryOp, EvalResult, Instruction, StackValue},
+        YIWR,
+    };
+
+    fn array(elements: Vec<StackValue>) -> StackValue {
+        StackValue::Array(Rc::new(RefCell::new(elements)))
+    }
+
+    #[test]
+    fn array_literal_bc() {
+        let yaiwr = &mut YIWR::new();
+        let ast = yaiwr.from_str("[1, true];").unwrap();
+        let bytecode = YIWR::ast_to_bytecode(ast);
+        match bytecode.as_slice() {
+            [first, second, third] => {
+                assert_eq!(
+                    first,
+                    &Instruction::Push {
+                        value: StackValue::Integer(1)
+                    }
+                );
+                assert_eq!(
+                    second,
+                    &Instruction::Push {
+                        value: StackValue::Boolean(true)
+                    }
+                );
+                assert_eq!(third, &Instruction::Array { len: 2 });
+            }
+            _ => panic!("expected bytecodes to be not empty!"),
+        }
+    }
+
+    #[test]
+    fn index_bc() {
+        let yaiwr = &mut YIWR::new();
+        let ast = yaiwr.from_str("xs[0];").unwrap();
+        let bytecode = YIWR::ast_to_bytecode(ast);
+        match bytecode.as_slice() {
+            [first, second, third] => {
+                assert_eq!(
+                    first,
+                    &Instruction::Load {
+                        id: "xs".to_string()
+                    }
+                );
+                assert_eq!(
+                    second,
+                    &Instruction::Push {
+                        value: StackValue::Integer(0)
+                    }
+                );
+                assert_eq!(
+                    third,
+                    &Instruction::BinaryOp {
+                        op: BinaryOp::Index
+                    }
+                );
+            }
+            _ => panic!("expected bytecodes to be not empty!"),
+        }
+    }
+
+    #[test]
+    fn index_assign_bc() {
+        let yaiwr = &mut YIWR::new();
+        let ast = yaiwr.from_str("xs[0] = 2;").unwrap();
+        let bytecode = YIWR::ast_to_bytecode(ast);
+        match bytecode.as_slice() {
+            [_, _, third, fourth] => {
+                assert_eq!(
+                    third,
+                    &Instruction::Push {
+                        value: StackValue::Integer(2)
+                    }
+                );
+                assert_eq!(fourth, &Instruction::StoreIndex);
+            }
+            _ => panic!("expected bytecodes to be not empty!"),
+        }
+    }
+
+    #[test]
+    fn array_eval() {
+        assert_eq!(
+            YIWR::eval_input("[1, 1 + 1, []];".to_string())
+                .unwrap()
+                .unwrap(),
+            EvalResult::Value(array(vec![
+                StackValue::Integer(1),
+                StackValue::Integer(2),
+                array(vec![])
+            ]))
+        );
+    }
+
+    #[test]
+    fn index_eval() {
+        assert_eq!(
+            YIWR::eval_input("let xs = [[1, 2], [3, 4]]; xs[1][0] + xs[0][1];".to_string())
+                .unwrap()
+                .unwrap(),
+            EvalResult::Value(StackValue::Integer(5))
+        );
+    }
+
+    #[test]
+    fn index_assign_eval() {
+        assert_eq!(
+            YIWR::eval_input("let xs = [1, 2]; xs[1] = xs[0] + 10; xs;".to_string())
+                .unwrap()
+                .unwrap(),
+            EvalResult::Value(array(vec![StackValue::Integer(1), StackValue::Integer(11)]))
+        );
+    }
+
+    #[test]
+    fn arrays_are_shared() {
+        let result = YIWR::eval_input(
+            "
+            fun set_first(arr, v) {
+                arr[0] = v;
+            }
+            let xs = [1, 2];
+            let ys = xs;
+            ys[1] = 20;
+            set_first(xs, 10);
+            xs;
+            "
+            .to_string(),
+        );
+        assert_eq!(
+            result.unwrap().unwrap(),
+            EvalResult::Value(array(vec![
+                StackValue::Integer(10),
+                StackValue::Integer(20)
+            ]))
+        );
+    }
+
+    #[test]
+    fn array_quotes_strings() {
+        let input = r#"["a", "b\n", ["say \"\${x}\""], "$1"];"#;
+        match YIWR::eval_input(input.to_string()).unwrap() {
+            Some(EvalResult::Value(val)) => {
+                assert_eq!(val.to_string(), r#"["a", "b\n", ["say \"\${x}\""], "$1"]"#)
+            }
+            res => panic!("expected a value, got {:?}", res),
+        }
+    }
+
+    #[test]
+    fn array_equality() {
+        assert_eq!(
+            YIWR::eval_input("[1, [true]] == [1, [true]];".to_string())
+                .unwrap()
+                .unwrap(),
+            EvalResult::Value(StackValue::Boolean(true))
+        );
+    }
+
+    #[test]
+    fn index_out_of_bounds_err() {
+        assert_eq!(
+            YIWR::eval_input("let xs = [1, 2]; xs[2];".to_string()),
+            Err(InterpError::IndexOutOfBounds(2, 2))
+        );
+        assert_eq!(
+            YIWR::eval_input("let xs = [1, 2]; xs[-1] = 0;".to_string()),
+            Err(InterpError::IndexOutOfBounds(-1, 2))
+        );
+    }
+
+    #[test]
+    fn index_non_array_err() {
+        assert_eq!(
+            YIWR::eval_input("let a = 1; a[0];".to_string()),
+            Err(InterpError::EvalError("Cannot index into 1".to_string()))
+        );
+    }
+}
@@ -0,0 +1,134 @@
+#[cfg(test)]
+mod tests {
+    use yaiwr::{
+        bigint::BigInt,
+        err::InterpError,
+        instruction::{EvalResult, StackValue},
+        scope::Scope,
+        YIWR,
+    };
+
+    fn eval_bignum(input: &str) -> Result<Option<EvalResult>, InterpError> {
+        let yaiwr = &mut YIWR::with_bignum();
+        let ast = yaiwr.from_str(input).unwrap();
+        let bytecode = YIWR::ast_to_bytecode(ast);
+        yaiwr.eval(&bytecode, Scope::new())
+    }
+
+    fn eval_to_string(input: &str) -> String {
+        match eval_bignum(input).unwrap() {
+            Some(EvalResult::Value(val)) => val.to_string(),
+            res => panic!("expected a value, got {:?}", res),
+        }
+    }
+
+    #[test]
+    fn bignum_overflow_promotes() {
+        let result = eval_bignum(format!("{} * 10;", i64::MAX).as_str());
+        match result.unwrap() {
+            Some(EvalResult::Value(StackValue::BigInt(val))) => {
+                assert_eq!(val.to_string(), "92233720368547758070")
+            }
+            res => panic!("expected a big integer, got {:?}", res),
+        }
+    }
+
+    #[test]
+    fn bignum_small_results_stay_integers() {
+        assert_eq!(
+            eval_bignum(format!("{} * 10 / 10 - {};", i64::MAX, i64::MAX).as_str()).unwrap(),
+            Some(EvalResult::Value(StackValue::Integer(0)))
+        );
+    }
+
+    #[test]
+    fn bignum_arithmetic() {
+        let big = "let b = 9223372036854775807 * 9223372036854775807; let d = 4294967296 * 4294967296 * 3 + 7;";
+        for (expr, expected) in [
+            ("b;", "85070591730234615847396907784232501249"),
+            ("b + b;", "170141183460469231694793815568465002498"),
+            ("-b;", "-85070591730234615847396907784232501249"),
+            ("b - b * 2;", "-85070591730234615847396907784232501249"),
+            ("b / d;", "1537228672809129300"),
+            ("b % d;", "44579631511464749749"),
+            ("-b % 1000;", "-249"),
+            ("-b / (10000000000 * 10000000000);", "-850705917302346158"),
+        ] {
+            assert_eq!(
+                eval_to_string(&format!("{} {}", big, expr)),
+                expected,
+                "{}",
+                expr
+            );
+        }
+    }
+
+    #[test]
+    fn bignum_comparison() {
+        for (expr, expected) in [
+            ("b > 1;", true),
+            ("-b < 1;", true),
+            ("b == b * 1;", true),
+            ("b != b + 1;", true),
+            ("b < 1.0;", false),
+        ] {
+            assert_eq!(
+                eval_bignum(&format!("let b = {} * 2; {}", i64::MAX, expr)).unwrap(),
+                Some(EvalResult::Value(StackValue::Boolean(expected))),
+                "{}",
+                expr
+            );
+        }
+    }
+
+    #[test]
+    fn bignum_negate_min() {
+        assert_eq!(
+            eval_to_string(&format!("let m = {} - 1; -m;", i64::MIN + 1)),
+            "9223372036854775808"
+        );
+    }
+
+    #[test]
+    fn bignum_division_by_zero() {
+        assert_eq!(
+            eval_bignum(format!("{} * 2 / 0;", i64::MAX).as_str()),
+            Err(InterpError::Numeric("division by zero".to_string()))
+        );
+    }
+
+    #[test]
+    fn bignum_shift() {
+        assert_eq!(eval_to_string("1 << 70;"), "1180591620717411303424");
+        assert_eq!(eval_to_string("-3 << 64;"), "-55340232221128654848");
+        assert_eq!(eval_to_string("(1 << 70) >> 70;"), "1");
+        assert_eq!(eval_to_string("(-(1 << 70) - 1) >> 69;"), "-3");
+        assert_eq!(eval_to_string("-(1 << 70) >> 200;"), "-1");
+        assert_eq!(eval_to_string("(1 << 70) >> 200;"), "0");
+        assert_eq!(
+            eval_bignum("(1 << 70) >> 7;").unwrap(),
+            Some(EvalResult::Value(StackValue::BigInt(
+                &BigInt::from(1 << 62) * &BigInt::from(2)
+            )))
+        );
+        assert_eq!(
+            eval_bignum("(1 << 70) >> 10;").unwrap(),
+            Some(EvalResult::Value(StackValue::Integer(1 << 60)))
+        );
+        assert_eq!(
+            eval_bignum("1 << -1;"),
+            Err(InterpError::Numeric(
+                "shift amount -1 is out of range".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn bigint_i64_round_trip() {
+        for val in [0, 1, -1, i64::MAX, i64::MIN, 1 << 32, -(1 << 32)] {
+            let big = BigInt::from(val);
+            assert_eq!(big.to_i64(), Some(val));
+            assert_eq!(big.to_string(), val.to_string());
+        }
+    }
+}
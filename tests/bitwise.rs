@@ -0,0 +1,137 @@
+#[cfg(test)]
+mod tests {
+    use yaiwr::{
+        err::InterpError,
+        instruction::{BinaryOp, EvalResult, Instruction, StackValue, UnaryOp},
+        YIWR,
+    };
+
+    #[test]
+    fn bit_and_bc() {
+        let yaiwr = &mut YIWR::new();
+        let ast = yaiwr.from_str("6 & 3;").unwrap();
+        let bytecode = YIWR::ast_to_bytecode(ast);
+        match bytecode.as_slice() {
+            [first, second, third] => {
+                assert_eq!(
+                    first,
+                    &Instruction::Push {
+                        value: StackValue::Integer(6)
+                    }
+                );
+                assert_eq!(
+                    second,
+                    &Instruction::Push {
+                        value: StackValue::Integer(3)
+                    }
+                );
+                assert_eq!(
+                    third,
+                    &Instruction::BinaryOp {
+                        op: BinaryOp::BitAnd
+                    }
+                );
+            }
+            _ => panic!("expected bytecodes to be not empty!"),
+        }
+    }
+
+    #[test]
+    fn bit_not_bc() {
+        let yaiwr = &mut YIWR::new();
+        let ast = yaiwr.from_str("~a;").unwrap();
+        let bytecode = YIWR::ast_to_bytecode(ast);
+        match bytecode.as_slice() {
+            [first, second] => {
+                assert_eq!(
+                    first,
+                    &Instruction::Load {
+                        id: "a".to_string()
+                    }
+                );
+                assert_eq!(
+                    second,
+                    &Instruction::UnaryOp {
+                        op: UnaryOp::BitNot
+                    }
+                );
+            }
+            _ => panic!("expected bytecodes to be not empty!"),
+        }
+    }
+
+    #[test]
+    fn shift_binds_looser_than_add_bc() {
+        let yaiwr = &mut YIWR::new();
+        let ast = yaiwr.from_str("1 + 1 << 2;").unwrap();
+        let bytecode = YIWR::ast_to_bytecode(ast);
+        match bytecode.as_slice() {
+            [.., add, _, shift] => {
+                assert_eq!(add, &Instruction::BinaryOp { op: BinaryOp::Add });
+                assert_eq!(
+                    shift,
+                    &Instruction::BinaryOp {
+                        op: BinaryOp::ShiftLeft
+                    }
+                );
+            }
+            _ => panic!("expected bytecodes to be not empty!"),
+        }
+    }
+
+    #[test]
+    fn eval_bitwise_expressions() {
+        for (input, expected) in [
+            ("6 & 3;", 2),
+            ("6 | 3;", 7),
+            ("6 ^ 3;", 5),
+            ("~5;", -6),
+            ("1 << 4;", 16),
+            ("-16 >> 2;", -4),
+            ("1 << 63;", i64::MIN),
+            // & binds tighter than ^, which binds tighter than |
+            ("1 | 6 ^ 3 & 5;", 7),
+            ("(1 | 6) ^ 3 & 5;", 6),
+        ] {
+            assert_eq!(
+                YIWR::eval_input(input.to_string()).unwrap().unwrap(),
+                EvalResult::Value(StackValue::Integer(expected)),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn bitwise_with_comparison() {
+        assert_eq!(
+            YIWR::eval_input("(5 & 3) == 1 && 1 << 2 > 3;".to_string())
+                .unwrap()
+                .unwrap(),
+            EvalResult::Value(StackValue::Boolean(true))
+        );
+    }
+
+    #[test]
+    fn shift_out_of_range_err() {
+        for (input, amount) in [("1 << 64;", 64), ("1 >> 100;", 100), ("1 << -1;", -1)] {
+            assert_eq!(
+                YIWR::eval_input(input.to_string()),
+                Err(InterpError::Numeric(format!(
+                    "shift amount {} is out of range for a 64-bit integer",
+                    amount
+                )))
+            );
+        }
+    }
+
+    #[test]
+    fn bitwise_float_err() {
+        assert_eq!(
+            YIWR::eval_input("1.5 & 1;".to_string()),
+            Err(InterpError::EvalError(
+                "Expected StackValue Integer stack, got 1.5!".to_string()
+            ))
+        );
+    }
+}
@@ -2,7 +2,8 @@
 mod tests {
     use yaiwr::{
         ast::AstNode,
-        instruction::{BinaryOp, Instruction, StackValue},
+        err::InterpError,
+        instruction::{BinaryOp, EvalResult, Instruction, StackValue, UnaryOp},
         YIWR,
     };
 
@@ -205,7 +206,34 @@ mod tests {
         let ast = yaiwr.from_str("true && false;").unwrap();
         let bytecode = YIWR::ast_to_bytecode(ast);
         match bytecode.as_slice() {
-            [bc1, bc2, bc3] => {
+            [bc1, bc2] => {
+                assert_eq!(
+                    bc1,
+                    &Instruction::Push {
+                        value: StackValue::Boolean(true)
+                    }
+                );
+                assert_eq!(
+                    bc2,
+                    &Instruction::ShortCircuit {
+                        op: BinaryOp::LogicalAnd,
+                        rhs: vec![Instruction::Push {
+                            value: StackValue::Boolean(false)
+                        }]
+                    }
+                );
+            }
+            _ => panic!("expected bytecodes to be not empty!"),
+        }
+    }
+
+    #[test]
+    fn bool_or_bc() {
+        let yaiwr = &mut YIWR::new();
+        let ast = yaiwr.from_str("true || false;").unwrap();
+        let bytecode = YIWR::ast_to_bytecode(ast);
+        match bytecode.as_slice() {
+            [bc1, bc2] => {
                 assert_eq!(
                     bc1,
                     &Instruction::Push {
@@ -214,14 +242,156 @@ mod tests {
                 );
                 assert_eq!(
                     bc2,
+                    &Instruction::ShortCircuit {
+                        op: BinaryOp::LogicalOr,
+                        rhs: vec![Instruction::Push {
+                            value: StackValue::Boolean(false)
+                        }]
+                    }
+                );
+            }
+            _ => panic!("expected bytecodes to be not empty!"),
+        }
+    }
+
+    #[test]
+    fn bool_not_bc() {
+        let yaiwr = &mut YIWR::new();
+        let ast = yaiwr.from_str("!true;").unwrap();
+        let bytecode = YIWR::ast_to_bytecode(ast);
+        match bytecode.as_slice() {
+            [bc1, bc2] => {
+                assert_eq!(
+                    bc1,
                     &Instruction::Push {
-                        value: StackValue::Boolean(false)
+                        value: StackValue::Boolean(true)
+                    }
+                );
+                assert_eq!(bc2, &Instruction::UnaryOp { op: UnaryOp::Not });
+            }
+            _ => panic!("expected bytecodes to be not empty!"),
+        }
+    }
+
+    #[test]
+    fn bool_not_eval() {
+        assert_eq!(
+            YIWR::eval_input("!(1 > 2) && !false;".to_string())
+                .unwrap()
+                .unwrap(),
+            EvalResult::Value(StackValue::Boolean(true))
+        );
+        assert_eq!(
+            YIWR::eval_input("!!false;".to_string()).unwrap().unwrap(),
+            EvalResult::Value(StackValue::Boolean(false))
+        );
+    }
+
+    #[test]
+    fn bool_not_integer_err() {
+        assert_eq!(
+            YIWR::eval_input("!1;".to_string()),
+            Err(InterpError::EvalError(
+                "Operand 1 cannot be applied to unary Not operation".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn bool_and_short_circuit_skips_rhs() {
+        let result = YIWR::eval_input(
+            "
+            let calls = 0;
+            fun f(x) {
+                calls = calls + 1;
+                return 10 / x > 1;
+            }
+            let x = 0;
+            x != 0 && f(x);
+            calls;
+            "
+            .to_string(),
+        );
+        assert_eq!(
+            result.unwrap(),
+            Some(EvalResult::Value(StackValue::Integer(0)))
+        );
+    }
+
+    #[test]
+    fn bool_or_short_circuit_skips_rhs() {
+        let result = YIWR::eval_input(
+            "
+            let calls = 0;
+            fun f() {
+                calls = calls + 1;
+                return false;
+            }
+            let r = true || f();
+            calls;
+            "
+            .to_string(),
+        );
+        assert_eq!(
+            result.unwrap(),
+            Some(EvalResult::Value(StackValue::Integer(0)))
+        );
+    }
+
+    #[test]
+    fn bool_and_evaluates_rhs_when_needed() {
+        let result = YIWR::eval_input(
+            "
+            let calls = 0;
+            fun f() {
+                calls = calls + 1;
+                return false;
+            }
+            let r = (true && f()) || f();
+            calls;
+            "
+            .to_string(),
+        );
+        assert_eq!(
+            result.unwrap(),
+            Some(EvalResult::Value(StackValue::Integer(2)))
+        );
+    }
+
+    #[test]
+    fn bool_or_non_boolean_lhs_err() {
+        assert_eq!(
+            YIWR::eval_input("1 || true;".to_string()),
+            Err(InterpError::EvalError(
+                "Operand 1 cannot be applied to logical LogicalOr operation".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn bool_less_than_or_equal_bc() {
+        // no whitespace, so the lexer must not split the operator
+        let yaiwr = &mut YIWR::new();
+        let ast = yaiwr.from_str("a<=b;").unwrap();
+        let bytecode = YIWR::ast_to_bytecode(ast);
+        match bytecode.as_slice() {
+            [bc1, bc2, bc3] => {
+                assert_eq!(
+                    bc1,
+                    &Instruction::Load {
+                        id: "a".to_string()
+                    }
+                );
+                assert_eq!(
+                    bc2,
+                    &Instruction::Load {
+                        id: "b".to_string()
                     }
                 );
                 assert_eq!(
                     bc3,
                     &Instruction::BinaryOp {
-                        op: BinaryOp::LogicalAnd
+                        op: BinaryOp::LessThanOrEqual
                     }
                 );
             }
@@ -230,32 +400,52 @@ mod tests {
     }
 
     #[test]
-    fn bool_or_bc() {
+    fn bool_greater_than_or_equal_bc() {
+        // no whitespace, so the lexer must not split the operator
         let yaiwr = &mut YIWR::new();
-        let ast = yaiwr.from_str("true || false;").unwrap();
+        let ast = yaiwr.from_str("a>=b;").unwrap();
         let bytecode = YIWR::ast_to_bytecode(ast);
         match bytecode.as_slice() {
             [bc1, bc2, bc3] => {
                 assert_eq!(
                     bc1,
-                    &Instruction::Push {
-                        value: StackValue::Boolean(true)
+                    &Instruction::Load {
+                        id: "a".to_string()
                     }
                 );
                 assert_eq!(
                     bc2,
-                    &Instruction::Push {
-                        value: StackValue::Boolean(false)
+                    &Instruction::Load {
+                        id: "b".to_string()
                     }
                 );
                 assert_eq!(
                     bc3,
                     &Instruction::BinaryOp {
-                        op: BinaryOp::LogicalOr
+                        op: BinaryOp::GreaterThanOrEqual
                     }
                 );
             }
             _ => panic!("expected bytecodes to be not empty!"),
         }
     }
+
+    #[test]
+    fn bool_less_greater_or_equal_eval() {
+        for (input, expected) in [
+            ("1 <= 2;", true),
+            ("2 <= 2;", true),
+            ("3 <= 2;", false),
+            ("1 >= 2;", false),
+            ("2 >= 2;", true),
+            ("-1 >= -2;", true),
+        ] {
+            assert_eq!(
+                YIWR::eval_input(input.to_string()).unwrap().unwrap(),
+                EvalResult::Value(StackValue::Boolean(expected)),
+                "{}",
+                input
+            );
+        }
+    }
 }
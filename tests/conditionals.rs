@@ -1,8 +1,10 @@
 #[cfg(test)]
 mod tests {
     use yaiwr::{
+        ast::AstNode,
+        err::InterpError,
         instruction::StackValue,
-        instruction::{BinaryOp, Instruction},
+        instruction::{BinaryOp, EvalResult, Instruction},
         YIWR,
     };
 
@@ -100,4 +102,138 @@ mod tests {
             _ => panic!("expected bytecodes to be not empty!"),
         }
     }
+
+    #[test]
+    fn ternary_bc() {
+        let yaiwr = &mut YIWR::new();
+        let ast = yaiwr.from_str("2 > 1 ? 10 : 20;").unwrap();
+        let bytecode = YIWR::ast_to_bytecode(ast);
+        match bytecode.as_slice() {
+            [first] => {
+                assert_eq!(
+                    first,
+                    &Instruction::Ternary {
+                        condition: vec![
+                            Instruction::Push {
+                                value: StackValue::Integer(2)
+                            },
+                            Instruction::Push {
+                                value: StackValue::Integer(1)
+                            },
+                            Instruction::BinaryOp {
+                                op: BinaryOp::GreaterThan {},
+                            }
+                        ],
+                        consequent: vec![Instruction::Push {
+                            value: StackValue::Integer(10)
+                        }],
+                        alternative: vec![Instruction::Push {
+                            value: StackValue::Integer(20)
+                        }],
+                    }
+                );
+            }
+            _ => panic!("expected bytecodes to be not empty!"),
+        }
+    }
+
+    #[test]
+    fn ternary_eval() {
+        assert_eq!(
+            YIWR::eval_input("1 > 2 ? 10 : 20;".to_string()).unwrap(),
+            Some(EvalResult::Value(StackValue::Integer(20)))
+        );
+        assert_eq!(
+            YIWR::eval_input("false ? 1 : true ? 2 : 3;".to_string()).unwrap(),
+            Some(EvalResult::Value(StackValue::Integer(2)))
+        );
+    }
+
+    #[test]
+    fn ternary_non_boolean_condition_err() {
+        assert!(matches!(
+            YIWR::eval_input("1 ? 2 : 3;".to_string()),
+            Err(InterpError::EvalError(..))
+        ));
+    }
+
+    #[test]
+    fn conditional_non_boolean_condition_err() {
+        assert!(matches!(
+            YIWR::eval_input("if (1) { 2; }".to_string()),
+            Err(InterpError::EvalError(..))
+        ));
+        assert!(matches!(
+            YIWR::eval_input("if (false) { 1; } else if (2) { 3; }".to_string()),
+            Err(InterpError::EvalError(..))
+        ));
+    }
+
+    #[test]
+    fn conditional_else_if_ast() {
+        let yaiwr = &mut YIWR::new();
+        let ast = yaiwr
+            .from_str("if (a) { 1; } else if (b) { 2; } else { 3; }")
+            .unwrap();
+        assert_eq!(
+            ast,
+            vec![AstNode::Conditional {
+                condition: Box::new(AstNode::ID {
+                    value: "a".to_string()
+                }),
+                block: vec![AstNode::Number { value: 1 }],
+                alternative: Some(vec![AstNode::Conditional {
+                    condition: Box::new(AstNode::ID {
+                        value: "b".to_string()
+                    }),
+                    block: vec![AstNode::Number { value: 2 }],
+                    alternative: Some(vec![AstNode::Number { value: 3 }]),
+                }]),
+            }]
+        );
+    }
+
+    #[test]
+    fn conditional_else_if_returns_from_every_arm() {
+        let prog = "
+            fun classify(n) {
+                if (n < 0) {
+                    return 1;
+                } else if (n == 0) {
+                    return 2;
+                } else if (n < 10) {
+                    return 3;
+                } else {
+                    return 4;
+                }
+                return 0;
+            }
+        ";
+        for (arg, expected) in [("-5", 1), ("0", 2), ("5", 3), ("50", 4)] {
+            assert_eq!(
+                YIWR::eval_input(format!("{} classify({});", prog, arg)).unwrap(),
+                Some(EvalResult::Value(StackValue::Integer(expected)))
+            );
+        }
+    }
+
+    #[test]
+    fn conditional_else_if_without_else() {
+        let prog = "
+            fun classify(n) {
+                if (n == 1) {
+                    return 1;
+                } else if (n == 2) {
+                    return 2;
+                }
+                return 0;
+            }
+        ";
+        for (arg, expected) in [("1", 1), ("2", 2), ("3", 0)] {
+            assert_eq!(
+                YIWR::eval_input(format!("{} classify({});", prog, arg)).unwrap(),
+                Some(EvalResult::Value(StackValue::Integer(expected)))
+            );
+        }
+    }
 }
@@ -0,0 +1,149 @@
+#[cfg(test)]
+mod tests {
+    use yaiwr::{
+        err::InterpError,
+        instruction::{BinaryOp, EvalResult, Instruction, StackValue},
+        scope::Scope,
+        YIWR,
+    };
+
+    pub fn eval_prog(
+        yaiwr: &mut YIWR,
+        input: &str,
+        scope: Scope,
+    ) -> Result<Option<EvalResult>, InterpError> {
+        let ast = yaiwr.from_str(input)?;
+        let bytecode = YIWR::ast_to_bytecode(ast);
+        yaiwr.eval(&bytecode, scope)
+    }
+
+    #[test]
+    fn const_bc() {
+        let yaiwr = &mut YIWR::new();
+        let ast = yaiwr.from_str("const x = 1;").unwrap();
+        let bytecode = YIWR::ast_to_bytecode(ast);
+        match bytecode.as_slice() {
+            [bc1, bc2] => {
+                assert_eq!(
+                    bc1,
+                    &Instruction::Push {
+                        value: StackValue::Integer(1)
+                    }
+                );
+                assert_eq!(
+                    bc2,
+                    &Instruction::BinaryOp {
+                        op: BinaryOp::Const {
+                            name: "x".to_string()
+                        }
+                    }
+                );
+            }
+            _ => panic!("expected bytecodes to be not empty!"),
+        }
+    }
+
+    #[test]
+    fn const_read() {
+        assert_eq!(
+            YIWR::eval_input("const x = 2; x * 21;".to_string())
+                .unwrap()
+                .unwrap(),
+            EvalResult::Value(StackValue::Integer(42))
+        );
+    }
+
+    #[test]
+    fn const_assign_compile_err() {
+        let yaiwr = &mut YIWR::new();
+        assert_eq!(
+            yaiwr.from_str("const x = 1; x = 2;"),
+            Err(InterpError::ConstantAssignment("x".to_string()))
+        );
+        assert_eq!(
+            yaiwr.from_str("const x = 1; let x = 2;"),
+            Err(InterpError::ConstantAssignment("x".to_string()))
+        );
+        // rejected even though the function is never called
+        assert_eq!(
+            yaiwr.from_str("const x = 1; fun f() { x = 2; }"),
+            Err(InterpError::ConstantAssignment("x".to_string()))
+        );
+        assert_eq!(
+            yaiwr.from_str("fun f() { return 1; } f = 2;"),
+            Err(InterpError::ConstantAssignment("f".to_string()))
+        );
+    }
+
+    #[test]
+    fn const_assign_runtime_err() {
+        let scope = Scope::new();
+        let yaiwr = &mut YIWR::new();
+        eval_prog(yaiwr, "const x = 1; fun f() { return 1; }", scope.clone()).unwrap();
+        assert_eq!(
+            eval_prog(yaiwr, "x = 2;", scope.clone()),
+            Err(InterpError::ConstantAssignment("x".to_string()))
+        );
+        assert_eq!(
+            eval_prog(yaiwr, "let x = 2;", scope.clone()),
+            Err(InterpError::ConstantAssignment("x".to_string()))
+        );
+        assert_eq!(
+            eval_prog(yaiwr, "fun g() { f = 2; } g();", scope.clone()),
+            Err(InterpError::ConstantAssignment("f".to_string()))
+        );
+        assert_eq!(
+            eval_prog(yaiwr, "x;", scope).unwrap().unwrap(),
+            EvalResult::Value(StackValue::Integer(1))
+        );
+    }
+
+    #[test]
+    fn const_shadowed_in_inner_scope() {
+        assert_eq!(
+            YIWR::eval_input(
+                "
+                const x = 1;
+                fun f() { let x = 2; x = x + 1; return x; }
+                let total = 0;
+                for i in 0..3 { const x = i; total = total + x; }
+                f() * 10 + total + x;
+                "
+                .to_string()
+            )
+            .unwrap()
+            .unwrap(),
+            EvalResult::Value(StackValue::Integer(34))
+        );
+    }
+
+    #[test]
+    fn const_value_is_mutable() {
+        assert_eq!(
+            YIWR::eval_input("const a = [1, 2]; a[0] = 3; a[0];".to_string())
+                .unwrap()
+                .unwrap(),
+            EvalResult::Value(StackValue::Integer(3))
+        );
+    }
+
+    #[test]
+    fn nested_function_is_mutable() {
+        assert_eq!(
+            YIWR::eval_input(
+                "
+                fun outer() {
+                    fun inner() { return 1; }
+                    inner = fun() { return 2; };
+                    return inner();
+                }
+                outer();
+                "
+                .to_string()
+            )
+            .unwrap()
+            .unwrap(),
+            EvalResult::Value(StackValue::Integer(2))
+        );
+    }
+}
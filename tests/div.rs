@@ -0,0 +1,70 @@
+#[cfg(test)]
+mod tests {
+    use yaiwr::{
+        err::InterpError,
+        instruction::{BinaryOp, EvalResult, Instruction, StackValue},
+        YIWR,
+    };
+
+    #[test]
+    fn div_bc() {
+        let yaiwr = &mut YIWR::new();
+        let ast = yaiwr.from_str("6/2;").unwrap();
+        let bytecode = YIWR::ast_to_bytecode(ast);
+        match bytecode.as_slice() {
+            [first, second, third] => {
+                assert_eq!(
+                    first,
+                    &Instruction::Push {
+                        value: StackValue::Integer(6)
+                    }
+                );
+                assert_eq!(
+                    second,
+                    &Instruction::Push {
+                        value: StackValue::Integer(2)
+                    }
+                );
+                assert_eq!(third, &Instruction::BinaryOp { op: BinaryOp::Div });
+            }
+            _ => panic!("expected bytecodes to be not empty!"),
+        }
+    }
+
+    #[test]
+    fn eval_div_and_mod_expressions() {
+        assert_eq!(
+            YIWR::eval_input("7/2;".to_string()).unwrap().unwrap(),
+            EvalResult::Value(StackValue::Integer(3)),
+            "expected 7/2=3"
+        );
+        assert_eq!(
+            YIWR::eval_input("7%3;".to_string()).unwrap().unwrap(),
+            EvalResult::Value(StackValue::Integer(1)),
+            "expected 7%3=1"
+        );
+        assert_eq!(
+            YIWR::eval_input("2+10/5*3%4;".to_string())
+                .unwrap()
+                .unwrap(),
+            EvalResult::Value(StackValue::Integer(4)),
+            "expected 2+10/5*3%4=4"
+        );
+    }
+
+    #[test]
+    fn div_by_zero() {
+        assert_eq!(
+            YIWR::eval_input("1/0;".to_string()),
+            Err(InterpError::Numeric("division by zero".to_string()))
+        );
+    }
+
+    #[test]
+    fn mod_by_zero() {
+        assert_eq!(
+            YIWR::eval_input("1%0;".to_string()),
+            Err(InterpError::Numeric("modulo by zero".to_string()))
+        );
+    }
+}
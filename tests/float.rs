@@ -0,0 +1,123 @@
+#[cfg(test)]
+mod tests {
+    use yaiwr::{
+        err::InterpError,
+        instruction::{EvalResult, Instruction, StackValue},
+        YIWR,
+    };
+
+    #[test]
+    fn float_literal_bc() {
+        let yaiwr = &mut YIWR::new();
+        let ast = yaiwr.from_str("2.75;").unwrap();
+        let bytecode = YIWR::ast_to_bytecode(ast);
+        match bytecode.as_slice() {
+            [first] => {
+                assert_eq!(
+                    first,
+                    &Instruction::Push {
+                        value: StackValue::Float(2.75)
+                    }
+                );
+            }
+            _ => panic!("expected bytecodes to be not empty!"),
+        }
+    }
+
+    #[test]
+    fn float_exponent_literal_bc() {
+        let yaiwr = &mut YIWR::new();
+        let ast = yaiwr.from_str("-1e-9;").unwrap();
+        let bytecode = YIWR::ast_to_bytecode(ast);
+        match bytecode.as_slice() {
+            [first] => {
+                assert_eq!(
+                    first,
+                    &Instruction::Push {
+                        value: StackValue::Float(-1e-9)
+                    }
+                );
+            }
+            _ => panic!("expected bytecodes to be not empty!"),
+        }
+    }
+
+    #[test]
+    fn eval_mixed_arithmetic() {
+        for (input, expected) in [
+            ("1 + 0.5;", 1.5),
+            ("0.5 + 1;", 1.5),
+            ("4.0 / 2;", 2.0),
+            ("3 * 0.5;", 1.5),
+            ("2 - 0.25;", 1.75),
+            ("7.5 % 2;", 1.5),
+            ("let a = 2.5; -a;", -2.5),
+        ] {
+            assert_eq!(
+                YIWR::eval_input(input.to_string()).unwrap().unwrap(),
+                EvalResult::Value(StackValue::Float(expected)),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn eval_mixed_comparison() {
+        for (input, expected) in [
+            ("1 == 1.0;", true),
+            ("1.5 != 1;", true),
+            ("0.1 < 1;", true),
+            ("2 >= 2.0;", true),
+            ("2.5 > 3;", false),
+        ] {
+            assert_eq!(
+                YIWR::eval_input(input.to_string()).unwrap().unwrap(),
+                EvalResult::Value(StackValue::Boolean(expected)),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn float_division_by_zero_is_infinite() {
+        assert_eq!(
+            YIWR::eval_input("1.0 / 0;".to_string()).unwrap().unwrap(),
+            EvalResult::Value(StackValue::Float(f64::INFINITY))
+        );
+    }
+
+    #[test]
+    fn float_display_round_trips() {
+        for value in [2.0, 0.1, 1e-9, 1e300, -3.5] {
+            let printed = StackValue::Float(value).to_string();
+            assert_eq!(printed.parse::<f64>().unwrap(), value);
+            assert!(
+                printed.contains('.') || printed.contains('e'),
+                "{}",
+                printed
+            );
+        }
+    }
+
+    #[test]
+    fn eq_result_can_be_used_in_expression() {
+        assert_eq!(
+            YIWR::eval_input("1 + (2 == 2 ? 1 : 0);".to_string())
+                .unwrap()
+                .unwrap(),
+            EvalResult::Value(StackValue::Integer(2))
+        );
+    }
+
+    #[test]
+    fn float_with_boolean_err() {
+        assert_eq!(
+            YIWR::eval_input("1.5 * true;".to_string()),
+            Err(InterpError::EvalError(
+                "Expected numeric StackValue, got true!".to_string()
+            ))
+        );
+    }
+}
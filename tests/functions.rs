@@ -243,4 +243,190 @@ mod tests {
             _ => panic!("expected bytecodes to be not empty!"),
         }
     }
+
+    #[test]
+    fn lambda_bc() {
+        let yaiwr = &mut YIWR::new();
+        let ast = yaiwr.from_str("let id = fun(x) { return x; };").unwrap();
+        let bytecode = YIWR::ast_to_bytecode(ast);
+        match bytecode.as_slice() {
+            [first, _] => {
+                assert_eq!(
+                    first,
+                    &Instruction::Lambda {
+                        params: vec!["x".to_string()],
+                        block: vec![Instruction::Return {
+                            block: vec![Instruction::Load {
+                                id: "x".to_string()
+                            }]
+                        }]
+                    }
+                );
+            }
+            _ => panic!("expected bytecodes to be not empty!"),
+        }
+    }
+
+    #[test]
+    fn lambda_call() {
+        let scope = Scope::new();
+        let yaiwr = &mut YIWR::new();
+        eval_prog(
+            yaiwr,
+            "let add = fun(a, b) { return a + b; };",
+            scope.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            eval_prog(yaiwr, "add(1, 2);", scope).unwrap().unwrap(),
+            EvalResult::Value(StackValue::Integer(3))
+        );
+    }
+
+    #[test]
+    fn lambda_inline_argument() {
+        let scope = Scope::new();
+        let yaiwr = &mut YIWR::new();
+        eval_prog(yaiwr, "fun apply(f, x) { return f(x); }", scope.clone()).unwrap();
+        assert_eq!(
+            eval_prog(yaiwr, "apply(fun(x) { return x * 2; }, 21);", scope)
+                .unwrap()
+                .unwrap(),
+            EvalResult::Value(StackValue::Integer(42))
+        );
+    }
+
+    #[test]
+    fn lambda_captures_defining_scope() {
+        let scope = Scope::new();
+        let yaiwr = &mut YIWR::new();
+        eval_prog(
+            yaiwr,
+            "
+            fun make_adder(n) {
+                return fun(x) { return x + n; };
+            }
+            let add5 = make_adder(5);
+            ",
+            scope.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            eval_prog(yaiwr, "add5(1);", scope).unwrap().unwrap(),
+            EvalResult::Value(StackValue::Integer(6))
+        );
+    }
+
+    #[test]
+    fn lambda_arguments_err() {
+        let scope = Scope::new();
+        let yaiwr = &mut YIWR::new();
+        assert_eq!(
+            eval_prog(yaiwr, "let f = fun(a) { return a; }; f();", scope),
+            Err(InterpError::FunctionArgumentsMissmatch(
+                "anonymous".to_string(),
+                1,
+                0
+            ))
+        );
+    }
+
+    #[test]
+    fn call_expression_bc() {
+        let yaiwr = &mut YIWR::new();
+        let ast = yaiwr.from_str("f(1)(2);").unwrap();
+        let bytecode = YIWR::ast_to_bytecode(ast);
+        match bytecode.as_slice() {
+            [first, second] => {
+                assert_eq!(
+                    first,
+                    &Instruction::FunctionCall {
+                        id: "f".to_string(),
+                        args: vec![vec![Instruction::Push {
+                            value: StackValue::Integer(1)
+                        }]]
+                    }
+                );
+                assert_eq!(
+                    second,
+                    &Instruction::Call {
+                        args: vec![vec![Instruction::Push {
+                            value: StackValue::Integer(2)
+                        }]]
+                    }
+                );
+            }
+            _ => panic!("expected bytecodes to be not empty!"),
+        }
+    }
+
+    #[test]
+    fn call_returned_function() {
+        let scope = Scope::new();
+        let yaiwr = &mut YIWR::new();
+        eval_prog(
+            yaiwr,
+            "fun make_adder(n) { return fun(x) { return x + n; }; }",
+            scope.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            eval_prog(yaiwr, "make_adder(1)(2);", scope)
+                .unwrap()
+                .unwrap(),
+            EvalResult::Value(StackValue::Integer(3))
+        );
+    }
+
+    #[test]
+    fn call_indexed_function() {
+        let scope = Scope::new();
+        let yaiwr = &mut YIWR::new();
+        eval_prog(
+            yaiwr,
+            "let handlers = [fun(x) { return x + 1; }, fun(x) { return x * 10; }];",
+            scope.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            eval_prog(yaiwr, "handlers[0](1) + handlers[1](2);", scope)
+                .unwrap()
+                .unwrap(),
+            EvalResult::Value(StackValue::Integer(22))
+        );
+    }
+
+    #[test]
+    fn call_not_callable_err() {
+        let scope = Scope::new();
+        let yaiwr = &mut YIWR::new();
+        assert_eq!(
+            eval_prog(yaiwr, "let xs = [true]; xs[0](1);", scope),
+            Err(InterpError::NotCallable("true".to_string()))
+        );
+    }
+
+    #[test]
+    fn call_not_callable_id_err() {
+        let scope = Scope::new();
+        let yaiwr = &mut YIWR::new();
+        assert_eq!(
+            eval_prog(yaiwr, "let x = 3; x(1);", scope),
+            Err(InterpError::NotCallable("3".to_string()))
+        );
+    }
+
+    #[test]
+    fn return_void_after_value() {
+        let scope = Scope::new();
+        let yaiwr = &mut YIWR::new();
+        assert_eq!(
+            eval_prog(
+                yaiwr,
+                "fun g() { } fun f() { let x = 5; return g(); } f();",
+                scope
+            ),
+            Ok(None)
+        );
+    }
 }
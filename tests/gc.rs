@@ -0,0 +1,134 @@
+#[cfg(test)]
+mod tests {
+    use yaiwr::{
+        err::InterpError,
+        gc,
+        instruction::{EvalResult, StackValue},
+        scope::Scope,
+        YIWR,
+    };
+
+    pub fn eval_prog(
+        yaiwr: &mut YIWR,
+        input: &str,
+        scope: Scope,
+    ) -> Result<Option<EvalResult>, InterpError> {
+        let ast = yaiwr.from_str(input).unwrap();
+        let bytecode = YIWR::ast_to_bytecode(ast);
+        yaiwr.eval(&bytecode, scope)
+    }
+
+    fn define_functions_in_loop(n: i64) {
+        let scope = Scope::new();
+        let yaiwr = &mut YIWR::new();
+        let prog = format!(
+            "
+            let total = 0;
+            for i in 0..{} {{
+                fun f() {{ return i; }}
+                total = total + f();
+            }}
+            ",
+            n
+        );
+        eval_prog(yaiwr, &prog, scope).unwrap();
+    }
+
+    #[test]
+    fn gc_bounded_memory_across_function_definitions() {
+        gc::collect();
+        let before = gc::live_objects();
+        define_functions_in_loop(100);
+        gc::collect();
+        let after_small = gc::live_objects();
+        define_functions_in_loop(10000);
+        gc::collect();
+        assert_eq!(after_small, before);
+        assert_eq!(gc::live_objects(), before);
+    }
+
+    #[test]
+    fn gc_collects_while_running() {
+        gc::collect();
+        let scope = Scope::new();
+        let yaiwr = &mut YIWR::new();
+        eval_prog(
+            yaiwr,
+            "
+            for i in 0..10000 {
+                let f = fun() { return i; };
+                let a = [f];
+                a[0] = a;
+            }
+            ",
+            scope,
+        )
+        .unwrap();
+        // without collection every iteration would leave its scope behind
+        assert!(gc::live_objects() < 5000);
+    }
+
+    #[test]
+    fn gc_keeps_reachable_closures() {
+        let scope = Scope::new();
+        let yaiwr = &mut YIWR::new();
+        eval_prog(
+            yaiwr,
+            "
+            fun make_counter() {
+                let n = 0;
+                return fun() { n = n + 1; return n; };
+            }
+            let counters = {\"a\": make_counter()};
+            counters[\"a\"]();
+            ",
+            scope.clone(),
+        )
+        .unwrap();
+        let held = eval_prog(yaiwr, "make_counter();", scope.clone()).unwrap();
+        gc::collect();
+        assert_eq!(
+            eval_prog(yaiwr, "counters[\"a\"]();", scope.clone())
+                .unwrap()
+                .unwrap(),
+            EvalResult::Value(StackValue::Integer(2))
+        );
+        // a function held outside of the interpreter survives as well
+        scope.dec_var(
+            "held".to_string(),
+            match held {
+                Some(EvalResult::Value(f)) => f,
+                _ => panic!("expected a function"),
+            },
+        );
+        assert_eq!(
+            eval_prog(yaiwr, "held();", scope).unwrap().unwrap(),
+            EvalResult::Value(StackValue::Integer(1))
+        );
+    }
+
+    #[test]
+    fn gc_frees_self_referencing_values() {
+        gc::collect();
+        let before = gc::live_objects();
+        {
+            let scope = Scope::new();
+            let yaiwr = &mut YIWR::new();
+            eval_prog(
+                yaiwr,
+                "
+                let a = [1];
+                a[0] = a;
+                let m = {};
+                m[\"self\"] = m;
+                fun f() { return f; }
+                ",
+                scope,
+            )
+            .unwrap();
+        }
+        assert!(gc::live_objects() > before);
+        assert_eq!(gc::collect(), 3);
+        assert_eq!(gc::live_objects(), before);
+    }
+}
@@ -1,7 +1,8 @@
 #[cfg(test)]
 mod tests {
     use yaiwr::{
-        instruction::{Instruction, StackValue},
+        err::InterpError,
+        instruction::{EvalResult, Instruction, StackValue, UnaryOp},
         YIWR,
     };
 
@@ -22,4 +23,132 @@ mod tests {
             _ => panic!("expected bytecodes to be not empty!"),
         }
     }
+
+    #[test]
+    fn negative_integer_literal_bc() {
+        let yaiwr = &mut YIWR::new();
+        let ast = yaiwr.from_str("-1;").unwrap();
+        let bytecode = YIWR::ast_to_bytecode(ast);
+        match bytecode.as_slice() {
+            [first] => {
+                assert_eq!(
+                    first,
+                    &Instruction::Push {
+                        value: StackValue::Integer(-1)
+                    }
+                );
+            }
+            _ => panic!("expected bytecodes to be not empty!"),
+        }
+    }
+
+    #[test]
+    fn negate_bc() {
+        let yaiwr = &mut YIWR::new();
+        let ast = yaiwr.from_str("-a;").unwrap();
+        let bytecode = YIWR::ast_to_bytecode(ast);
+        match bytecode.as_slice() {
+            [first, second] => {
+                assert_eq!(
+                    first,
+                    &Instruction::Load {
+                        id: "a".to_string()
+                    }
+                );
+                assert_eq!(
+                    second,
+                    &Instruction::UnaryOp {
+                        op: UnaryOp::Negate
+                    }
+                );
+            }
+            _ => panic!("expected bytecodes to be not empty!"),
+        }
+    }
+
+    #[test]
+    fn eval_signed_expressions() {
+        assert_eq!(
+            YIWR::eval_input("let a = 5; -a * 2;".to_string())
+                .unwrap()
+                .unwrap(),
+            EvalResult::Value(StackValue::Integer(-10))
+        );
+        assert_eq!(
+            YIWR::eval_input("-7 / 2;".to_string()).unwrap().unwrap(),
+            EvalResult::Value(StackValue::Integer(-3))
+        );
+        assert_eq!(
+            YIWR::eval_input("-2 < 1;".to_string()).unwrap().unwrap(),
+            EvalResult::Value(StackValue::Boolean(true))
+        );
+    }
+
+    #[test]
+    fn eval_min_literal() {
+        assert_eq!(
+            YIWR::eval_input(format!("{};", i64::MIN)).unwrap().unwrap(),
+            EvalResult::Value(StackValue::Integer(i64::MIN))
+        );
+        assert_eq!(
+            YIWR::eval_input(format!("{} + 1;", i64::MIN))
+                .unwrap()
+                .unwrap(),
+            EvalResult::Value(StackValue::Integer(i64::MIN + 1))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "overflowed")]
+    fn negate_min_literal_overflow() {
+        YIWR::eval_input(format!("-{};", i64::MIN)).unwrap();
+    }
+
+    #[test]
+    fn integer_literal_too_large_err() {
+        let yaiwr = &mut YIWR::new();
+        // only fits once it is negated
+        assert_eq!(
+            yaiwr.from_str(&format!("{};", i64::MIN.unsigned_abs())),
+            Err(InterpError::ParseError(
+                "9223372036854775808 cannot be represented as a i64".to_string()
+            ))
+        );
+        assert!(matches!(
+            yaiwr.from_str(&format!("-({});", i64::MIN.unsigned_abs())),
+            Err(InterpError::ParseError(..))
+        ));
+        assert!(matches!(
+            yaiwr.from_str(&format!("{};", i64::MIN.unsigned_abs() + 1)),
+            Err(InterpError::ParseError(..))
+        ));
+        assert!(matches!(
+            yaiwr.from_str(&format!("-{};", i64::MIN.unsigned_abs() + 1)),
+            Err(InterpError::ParseError(..))
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = "overflowed")]
+    fn negate_min_overflow() {
+        let input = format!("let m = {} - 1; -m;", i64::MIN + 1);
+        YIWR::eval_input(input).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "underflowed")]
+    fn mul_underflow() {
+        let input = format!("{}*{};", i64::MAX, -2);
+        YIWR::eval_input(input).unwrap();
+    }
+
+    #[test]
+    fn negate_boolean_err() {
+        assert_eq!(
+            YIWR::eval_input("-true;".to_string()),
+            Err(InterpError::EvalError(
+                "Operand true cannot be applied to unary Negate operation".to_string()
+            ))
+        );
+    }
 }
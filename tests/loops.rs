@@ -0,0 +1,241 @@
+#[cfg(test)]
+mod tests {
+    use yaiwr::{
+        err::InterpError,
+        instruction::{BinaryOp, EvalResult, Instruction, StackValue},
+        YIWR,
+    };
+
+    #[test]
+    fn while_bc() {
+        let yaiwr = &mut YIWR::new();
+        let ast = yaiwr
+            .from_str(
+                "
+            while (i < 3) {
+                break;
+                continue;
+            }
+        ",
+            )
+            .unwrap();
+        let bytecode = YIWR::ast_to_bytecode(ast);
+        match bytecode.as_slice() {
+            [first] => {
+                assert_eq!(
+                    first,
+                    &Instruction::While {
+                        condition: vec![
+                            Instruction::Load {
+                                id: "i".to_string()
+                            },
+                            Instruction::Push {
+                                value: StackValue::Integer(3)
+                            },
+                            Instruction::BinaryOp {
+                                op: BinaryOp::LessThan
+                            }
+                        ],
+                        block: vec![Instruction::Break, Instruction::Continue]
+                    }
+                );
+            }
+            _ => panic!("expected bytecodes to be not empty!"),
+        }
+    }
+
+    #[test]
+    fn while_eval() {
+        let result = YIWR::eval_input(
+            "
+            let i = 0;
+            let sum = 0;
+            while (i < 5) {
+                i = i + 1;
+                sum = sum + i;
+            }
+            sum;
+            "
+            .to_string(),
+        );
+        assert_eq!(
+            result.unwrap(),
+            Some(EvalResult::Value(StackValue::Integer(15)))
+        );
+    }
+
+    #[test]
+    fn while_break_continue_eval() {
+        let result = YIWR::eval_input(
+            "
+            let i = 0;
+            let sum = 0;
+            while (true) {
+                i = i + 1;
+                if (i == 2) {
+                    continue;
+                }
+                if (i > 4) {
+                    break;
+                }
+                sum = sum + i;
+            }
+            sum;
+            "
+            .to_string(),
+        );
+        assert_eq!(
+            result.unwrap(),
+            Some(EvalResult::Value(StackValue::Integer(8)))
+        );
+    }
+
+    #[test]
+    fn while_return_from_nested_conditional() {
+        let result = YIWR::eval_input(
+            "
+            fun first_above(n) {
+                let i = 0;
+                while (true) {
+                    if (i > n) {
+                        if (true) {
+                            return i;
+                        }
+                    }
+                    i = i + 1;
+                }
+            }
+            first_above(7);
+            "
+            .to_string(),
+        );
+        assert_eq!(
+            result.unwrap(),
+            Some(EvalResult::Value(StackValue::Integer(8)))
+        );
+    }
+
+    #[test]
+    fn while_return_void_after_value() {
+        let result = YIWR::eval_input(
+            "
+            fun g() { }
+            fun f() {
+                while (true) {
+                    let x = 5;
+                    return g();
+                }
+            }
+            f();
+            "
+            .to_string(),
+        );
+        assert_eq!(result.unwrap(), None);
+    }
+
+    #[test]
+    fn while_non_boolean_condition_err() {
+        assert!(matches!(
+            YIWR::eval_input("while (1) { }".to_string()),
+            Err(InterpError::EvalError(..))
+        ));
+    }
+
+    #[test]
+    fn break_outside_loop_err() {
+        assert!(matches!(
+            YIWR::eval_input("fun f() { break; } f();".to_string()),
+            Err(InterpError::EvalError(..))
+        ));
+    }
+
+    #[test]
+    fn break_outside_loop_top_level_err() {
+        let yaiwr = &mut YIWR::new();
+        assert!(matches!(
+            yaiwr.from_str("break;"),
+            Err(InterpError::EvalError(..))
+        ));
+        assert!(matches!(
+            yaiwr.from_str("if (true) { { continue; } }"),
+            Err(InterpError::EvalError(..))
+        ));
+    }
+
+    #[test]
+    fn for_eval() {
+        let result = YIWR::eval_input(
+            "
+            let sum = 0;
+            for (let i = 0; i < 5; i = i + 1) {
+                if (i == 1) {
+                    continue;
+                }
+                sum = sum + i;
+            }
+            sum;
+            "
+            .to_string(),
+        );
+        assert_eq!(
+            result.unwrap(),
+            Some(EvalResult::Value(StackValue::Integer(9)))
+        );
+    }
+
+    #[test]
+    fn for_in_eval() {
+        let result = YIWR::eval_input(
+            "
+            let n = 4;
+            let sum = 0;
+            for i in 1..n + 1 {
+                sum = sum + i;
+            }
+            sum;
+            "
+            .to_string(),
+        );
+        assert_eq!(
+            result.unwrap(),
+            Some(EvalResult::Value(StackValue::Integer(10)))
+        );
+    }
+
+    #[test]
+    fn for_loop_variable_does_not_leak() {
+        assert_eq!(
+            YIWR::eval_input("for (let i = 0; i < 2; i = i + 1) { } i;".to_string()),
+            Err(InterpError::UndefinedReference("i".to_string()))
+        );
+        assert_eq!(
+            YIWR::eval_input("for i in 0..2 { } i;".to_string()),
+            Err(InterpError::UndefinedReference("i".to_string()))
+        );
+    }
+
+    #[test]
+    fn for_in_bc() {
+        let yaiwr = &mut YIWR::new();
+        let ast = yaiwr.from_str("for i in 0..n { break; }").unwrap();
+        let bytecode = YIWR::ast_to_bytecode(ast);
+        match bytecode.as_slice() {
+            [first] => {
+                assert_eq!(
+                    first,
+                    &Instruction::ForIn {
+                        id: "i".to_string(),
+                        start: vec![Instruction::Push {
+                            value: StackValue::Integer(0)
+                        }],
+                        end: vec![Instruction::Load {
+                            id: "n".to_string()
+                        }],
+                        block: vec![Instruction::Break]
+                    }
+                );
+            }
+            _ => panic!("expected bytecodes to be not empty!"),
+        }
+    }
+}
@@ -0,0 +1,160 @@
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+    use yaiwr::{
+        err::InterpError,
+        instruction::{EvalResult, Instruction, MapKey, StackValue},
+        YIWR,
+    };
+
+    #[test]
+    fn map_literal_bc() {
+        let yaiwr = &mut YIWR::new();
+        let ast = yaiwr.from_str("{\"a\": 1};").unwrap();
+        let bytecode = YIWR::ast_to_bytecode(ast);
+        match bytecode.as_slice() {
+            [first, second, third] => {
+                assert_eq!(
+                    first,
+                    &Instruction::Push {
+                        value: StackValue::Str("a".to_string())
+                    }
+                );
+                assert_eq!(
+                    second,
+                    &Instruction::Push {
+                        value: StackValue::Integer(1)
+                    }
+                );
+                assert_eq!(third, &Instruction::Map { len: 1 });
+            }
+            _ => panic!("expected bytecodes to be not empty!"),
+        }
+    }
+
+    #[test]
+    fn map_eval() {
+        let mut expected = HashMap::new();
+        expected.insert(MapKey::Str("a".to_string()), StackValue::Integer(1));
+        expected.insert(MapKey::Integer(2), StackValue::Boolean(true));
+        assert_eq!(
+            YIWR::eval_input("{\"a\": 1, 1 + 1: true};".to_string())
+                .unwrap()
+                .unwrap(),
+            EvalResult::Value(StackValue::Map(Rc::new(RefCell::new(expected))))
+        );
+    }
+
+    #[test]
+    fn map_lookup_and_insert() {
+        assert_eq!(
+            YIWR::eval_input(
+                "let m = {\"a\": 1}; m[\"b\"] = 2; m[\"a\"] = m[\"a\"] + 10; m[\"a\"] + m[\"b\"];"
+                    .to_string()
+            )
+            .unwrap()
+            .unwrap(),
+            EvalResult::Value(StackValue::Integer(13))
+        );
+    }
+
+    #[test]
+    fn maps_are_shared() {
+        let result = YIWR::eval_input(
+            "
+            fun add(m, k, v) {
+                m[k] = v;
+            }
+            let m = {};
+            let alias = m;
+            add(alias, \"x\", 1);
+            m[\"x\"];
+            "
+            .to_string(),
+        );
+        assert_eq!(
+            result.unwrap().unwrap(),
+            EvalResult::Value(StackValue::Integer(1))
+        );
+    }
+
+    #[test]
+    fn map_keys_sorted() {
+        assert_eq!(
+            YIWR::eval_input("keys({\"b\": 1, \"a\": 2, 3: 3});".to_string())
+                .unwrap()
+                .unwrap(),
+            EvalResult::Value(StackValue::Array(Rc::new(RefCell::new(vec![
+                StackValue::Integer(3),
+                StackValue::Str("a".to_string()),
+                StackValue::Str("b".to_string())
+            ]))))
+        );
+    }
+
+    #[test]
+    fn map_quotes_strings() {
+        let input = r#"{"a": "x", "b\"": ["y"], 1: true};"#;
+        match YIWR::eval_input(input.to_string()).unwrap() {
+            Some(EvalResult::Value(val)) => {
+                assert_eq!(val.to_string(), r#"{1: true, "a": "x", "b\"": ["y"]}"#)
+            }
+            res => panic!("expected a value, got {:?}", res),
+        }
+    }
+
+    #[test]
+    fn map_equality() {
+        for (input, expected) in [
+            ("{\"a\": 1} == {\"a\": 1.0};", true),
+            ("{\"a\": [1]} == {\"a\": [1]};", true),
+            ("{\"a\": 1} == {\"a\": 1, \"b\": 2};", false),
+            ("{\"a\": 1} != {\"b\": 1};", true),
+            ("{} == {};", true),
+        ] {
+            assert_eq!(
+                YIWR::eval_input(input.to_string()).unwrap().unwrap(),
+                EvalResult::Value(StackValue::Boolean(expected)),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn map_missing_key_err() {
+        assert_eq!(
+            YIWR::eval_input("let m = {\"a\": 1}; m[\"b\"];".to_string()),
+            Err(InterpError::KeyNotFound("b".to_string()))
+        );
+    }
+
+    #[test]
+    fn map_invalid_key_err() {
+        assert_eq!(
+            YIWR::eval_input("{1.5: 1};".to_string()),
+            Err(InterpError::EvalError(
+                "1.5 cannot be used as a map key".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn keys_arguments_err() {
+        assert_eq!(
+            YIWR::eval_input("keys(1);".to_string()),
+            Err(InterpError::EvalError(
+                "Expected StackValue Map, got 1".to_string()
+            ))
+        );
+        assert_eq!(
+            YIWR::eval_input("keys({}, {});".to_string()),
+            Err(InterpError::FunctionArgumentsMissmatch(
+                "keys".to_string(),
+                1,
+                2
+            ))
+        );
+    }
+}
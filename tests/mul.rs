@@ -60,7 +60,7 @@ mod tests {
     fn mul_overflow() {
         let scope = Scope::new();
         let yaiwr = &mut YIWR::new();
-        let input = format!("{}*{};", u64::MAX, 2);
+        let input = format!("{}*{};", i64::MAX, 2);
         let ast = yaiwr.from_str(input.as_str()).unwrap();
         let bytecode = YIWR::ast_to_bytecode(ast);
         yaiwr.eval(&bytecode, scope).unwrap();
@@ -70,7 +70,7 @@ mod tests {
     fn mul_no_overflow() {
         let scope = Scope::new();
         let yaiwr = &mut YIWR::new();
-        let input = format!("{}*{};", u64::MAX, 1);
+        let input = format!("{}*{};", i64::MAX, 1);
         let ast = yaiwr.from_str(input.as_str()).unwrap();
         let bytecode = YIWR::ast_to_bytecode(ast);
         yaiwr.eval(&bytecode, scope).unwrap();
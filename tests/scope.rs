@@ -1,10 +1,23 @@
 #[cfg(test)]
 mod tests {
     use yaiwr::{
-        instruction::StackValue,
+        ast::AstNode,
+        err::InterpError,
+        instruction::{EvalResult, StackValue},
         scope::{Object, Scope},
+        YIWR,
     };
 
+    pub fn eval_prog(
+        yaiwr: &mut YIWR,
+        input: &str,
+        scope: Scope,
+    ) -> Result<Option<EvalResult>, InterpError> {
+        let ast = yaiwr.from_str(input).unwrap();
+        let bytecode = YIWR::ast_to_bytecode(ast);
+        yaiwr.eval(&bytecode, scope)
+    }
+
     #[test]
     fn scope_var_get() {
         let scope = Scope::new();
@@ -77,4 +90,247 @@ mod tests {
         let result = outer_scope.set_var("a".to_string(), StackValue::Boolean(false));
         assert_eq!(result, None)
     }
+
+    #[test]
+    fn closure_counters_are_independent() {
+        let scope = Scope::new();
+        let yaiwr = &mut YIWR::new();
+        eval_prog(
+            yaiwr,
+            "
+            fun make_counter() {
+                let n = 0;
+                return fun() { n = n + 1; return n; };
+            }
+            let a = make_counter();
+            let b = make_counter();
+            a();
+            a();
+            ",
+            scope.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            eval_prog(yaiwr, "a();", scope.clone()).unwrap().unwrap(),
+            EvalResult::Value(StackValue::Integer(3))
+        );
+        assert_eq!(
+            eval_prog(yaiwr, "b();", scope).unwrap().unwrap(),
+            EvalResult::Value(StackValue::Integer(1))
+        );
+    }
+
+    #[test]
+    fn closure_sees_later_assignments() {
+        let scope = Scope::new();
+        let yaiwr = &mut YIWR::new();
+        eval_prog(
+            yaiwr,
+            "let x = 1; let get_x = fun() { return x; }; x = 2;",
+            scope.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            eval_prog(yaiwr, "get_x();", scope).unwrap().unwrap(),
+            EvalResult::Value(StackValue::Integer(2))
+        );
+    }
+
+    #[test]
+    fn closure_shadowing() {
+        let scope = Scope::new();
+        let yaiwr = &mut YIWR::new();
+        eval_prog(
+            yaiwr,
+            "
+            let x = 1;
+            fun shadow() {
+                let x = 2;
+                return fun() { x = x + 1; return x; };
+            }
+            let inner = shadow();
+            ",
+            scope.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            eval_prog(yaiwr, "inner();", scope.clone())
+                .unwrap()
+                .unwrap(),
+            EvalResult::Value(StackValue::Integer(3))
+        );
+        assert_eq!(
+            scope.get_var("x".to_string()).unwrap(),
+            Object::Value {
+                value: StackValue::Integer(1)
+            }
+        );
+    }
+
+    #[test]
+    fn closure_returned_from_nested_functions() {
+        let scope = Scope::new();
+        let yaiwr = &mut YIWR::new();
+        eval_prog(
+            yaiwr,
+            "
+            fun outer(a) {
+                fun middle(b) {
+                    return fun(c) { return a * 100 + b * 10 + c; };
+                }
+                return middle(a + 1);
+            }
+            let f = outer(1);
+            ",
+            scope.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            eval_prog(yaiwr, "f(3);", scope).unwrap().unwrap(),
+            EvalResult::Value(StackValue::Integer(123))
+        );
+    }
+
+    #[test]
+    fn closure_captures_loop_iteration() {
+        let scope = Scope::new();
+        let yaiwr = &mut YIWR::new();
+        eval_prog(
+            yaiwr,
+            "
+            let fs = [0, 0, 0];
+            for i in 0..3 { fs[i] = fun() { return i; }; }
+            let gs = [0, 0, 0];
+            let k = 0;
+            while (k < 3) { let j = k; gs[k] = fun() { return j; }; k = k + 1; }
+            ",
+            scope.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            eval_prog(yaiwr, "fs[0]() + fs[2]();", scope.clone())
+                .unwrap()
+                .unwrap(),
+            EvalResult::Value(StackValue::Integer(2))
+        );
+        assert_eq!(
+            eval_prog(yaiwr, "gs[0]() * 10 + gs[1]();", scope)
+                .unwrap()
+                .unwrap(),
+            EvalResult::Value(StackValue::Integer(1))
+        );
+    }
+
+    #[test]
+    fn scopes_compare_by_identity() {
+        let scope = Scope::new();
+        let yaiwr = &mut YIWR::new();
+        eval_prog(yaiwr, "fun f() { return 1; } let g = f;", scope.clone()).unwrap();
+        assert_eq!(
+            eval_prog(yaiwr, "f == g;", scope.clone()).unwrap().unwrap(),
+            EvalResult::Value(StackValue::Boolean(true))
+        );
+        assert_ne!(Scope::new(), Scope::new());
+        assert_eq!(scope.clone(), scope);
+    }
+
+    #[test]
+    fn if_body_shadows_outer_variable() {
+        let scope = Scope::new();
+        let yaiwr = &mut YIWR::new();
+        eval_prog(
+            yaiwr,
+            "
+            let x = 1;
+            let seen = 0;
+            if (true) { let x = 2; seen = x; } else { let x = 3; }
+            if (false) { let x = 4; } else { let x = 5; seen = seen * 10 + x; }
+            ",
+            scope.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            scope.get_var("x".to_string()).unwrap(),
+            Object::Value {
+                value: StackValue::Integer(1)
+            }
+        );
+        assert_eq!(
+            scope.get_var("seen".to_string()).unwrap(),
+            Object::Value {
+                value: StackValue::Integer(25)
+            }
+        );
+    }
+
+    #[test]
+    fn if_body_declarations_do_not_leak() {
+        let scope = Scope::new();
+        let yaiwr = &mut YIWR::new();
+        assert_eq!(
+            eval_prog(yaiwr, "if (true) { let y = 1; } y;", scope),
+            Err(InterpError::UndefinedReference("y".to_string()))
+        );
+    }
+
+    #[test]
+    fn block_statement_scope() {
+        let scope = Scope::new();
+        let yaiwr = &mut YIWR::new();
+        eval_prog(
+            yaiwr,
+            "
+            let x = 1;
+            let outer = 0;
+            {
+                let x = 2;
+                {
+                    let x = x + 1;
+                    outer = x;
+                }
+                outer = outer * 10 + x;
+            }
+            ",
+            scope.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            eval_prog(yaiwr, "outer * 10 + x;", scope.clone())
+                .unwrap()
+                .unwrap(),
+            EvalResult::Value(StackValue::Integer(321))
+        );
+        assert_eq!(scope.get_store_len(), 2);
+    }
+
+    #[test]
+    fn block_statement_return() {
+        assert_eq!(
+            YIWR::eval_input("fun f() { { return 7; } return 0; } f();".to_string())
+                .unwrap()
+                .unwrap(),
+            EvalResult::Value(StackValue::Integer(7))
+        );
+    }
+
+    #[test]
+    fn block_statement_ast() {
+        let yaiwr = &mut YIWR::new();
+        assert_eq!(
+            yaiwr.from_str("{} { 1; }").unwrap(),
+            vec![
+                AstNode::Block { block: vec![] },
+                AstNode::Block {
+                    block: vec![AstNode::Number { value: 1 }]
+                },
+            ]
+        );
+        // a map literal can still start a statement
+        assert_eq!(
+            yaiwr.from_str("{1: 2};").unwrap(),
+            vec![AstNode::Map {
+                entries: vec![(AstNode::Number { value: 1 }, AstNode::Number { value: 2 })]
+            }]
+        );
+    }
 }
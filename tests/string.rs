@@ -0,0 +1,160 @@
+#[cfg(test)]
+mod tests {
+    use yaiwr::{
+        ast::AstNode,
+        err::InterpError,
+        instruction::{BinaryOp, EvalResult, Instruction, StackValue},
+        YIWR,
+    };
+
+    #[test]
+    fn string_literal_bc() {
+        let yaiwr = &mut YIWR::new();
+        let ast = yaiwr.from_str("\"hello\";").unwrap();
+        assert_eq!(
+            ast[0],
+            AstNode::Str {
+                value: "hello".to_string()
+            }
+        );
+        let bytecode = YIWR::ast_to_bytecode(ast);
+        match bytecode.as_slice() {
+            [first] => {
+                assert_eq!(
+                    first,
+                    &Instruction::Push {
+                        value: StackValue::Str("hello".to_string())
+                    }
+                );
+            }
+            _ => panic!("expected bytecodes to be not empty!"),
+        }
+    }
+
+    #[test]
+    fn string_literal_escapes() {
+        let yaiwr = &mut YIWR::new();
+        let ast = yaiwr.from_str(r#""a\tb\n\"c\" \\";"#).unwrap();
+        assert_eq!(
+            ast[0],
+            AstNode::Str {
+                value: "a\tb\n\"c\" \\".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn string_literal_invalid_escape_err() {
+        let yaiwr = &mut YIWR::new();
+        assert!(matches!(
+            yaiwr.from_str(r#""\q";"#),
+            Err(InterpError::ParseError(..))
+        ));
+    }
+
+    #[test]
+    fn string_concatenation() {
+        assert_eq!(
+            YIWR::eval_input("let x = 5; \"total: \" + x;".to_string())
+                .unwrap()
+                .unwrap(),
+            EvalResult::Value(StackValue::Str("total: 5".to_string()))
+        );
+        assert_eq!(
+            YIWR::eval_input("1 + 2 + \"!\" + true;".to_string())
+                .unwrap()
+                .unwrap(),
+            EvalResult::Value(StackValue::Str("3!true".to_string()))
+        );
+    }
+
+    #[test]
+    fn string_equality() {
+        assert_eq!(
+            YIWR::eval_input("\"a\" == \"a\";".to_string())
+                .unwrap()
+                .unwrap(),
+            EvalResult::Value(StackValue::Boolean(true))
+        );
+        assert_eq!(
+            YIWR::eval_input("\"a\" != \"b\";".to_string())
+                .unwrap()
+                .unwrap(),
+            EvalResult::Value(StackValue::Boolean(true))
+        );
+        assert!(matches!(
+            YIWR::eval_input("\"1\" == 1;".to_string()),
+            Err(InterpError::EvalError(..))
+        ));
+    }
+
+    #[test]
+    fn string_interpolation_bc() {
+        let yaiwr = &mut YIWR::new();
+        let ast = yaiwr.from_str("\"a ${x} b ${y + 1}\";").unwrap();
+        let bytecode = YIWR::ast_to_bytecode(ast);
+        assert_eq!(
+            bytecode,
+            vec![
+                Instruction::Push {
+                    value: StackValue::Str("a ".to_string())
+                },
+                Instruction::Load {
+                    id: "x".to_string()
+                },
+                Instruction::BinaryOp { op: BinaryOp::Add },
+                Instruction::Push {
+                    value: StackValue::Str(" b ".to_string())
+                },
+                Instruction::BinaryOp { op: BinaryOp::Add },
+                Instruction::Load {
+                    id: "y".to_string()
+                },
+                Instruction::Push {
+                    value: StackValue::Integer(1)
+                },
+                Instruction::BinaryOp { op: BinaryOp::Add },
+                Instruction::BinaryOp { op: BinaryOp::Add },
+            ]
+        );
+    }
+
+    #[test]
+    fn string_interpolation_eval() {
+        assert_eq!(
+            YIWR::eval_input(
+                "let name = \"bob\"; let age = 41; \"hello ${name}, you are ${age + 1}\";"
+                    .to_string()
+            )
+            .unwrap()
+            .unwrap(),
+            EvalResult::Value(StackValue::Str("hello bob, you are 42".to_string()))
+        );
+        assert_eq!(
+            YIWR::eval_input("\"${1}${2}\";".to_string())
+                .unwrap()
+                .unwrap(),
+            EvalResult::Value(StackValue::Str("12".to_string()))
+        );
+    }
+
+    #[test]
+    fn string_interpolation_nested_strings() {
+        assert_eq!(
+            YIWR::eval_input("let n = 1; \"<${ n > 0 ? \"pos ${n}\" : \"neg\" }>\";".to_string())
+                .unwrap()
+                .unwrap(),
+            EvalResult::Value(StackValue::Str("<pos 1>".to_string()))
+        );
+    }
+
+    #[test]
+    fn string_dollar_without_interpolation() {
+        assert_eq!(
+            YIWR::eval_input("\"$5 \\${x} $$\";".to_string())
+                .unwrap()
+                .unwrap(),
+            EvalResult::Value(StackValue::Str("$5 ${x} $$".to_string()))
+        );
+    }
+}
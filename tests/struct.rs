@@ -0,0 +1,216 @@
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use yaiwr::{
+        err::InterpError,
+        instruction::{EvalResult, Instruction, StackValue, StructValue},
+        YIWR,
+    };
+
+    #[test]
+    fn struct_declaration_bc() {
+        let yaiwr = &mut YIWR::new();
+        let ast = yaiwr.from_str("struct Point { x, y }").unwrap();
+        let bytecode = YIWR::ast_to_bytecode(ast);
+        match bytecode.as_slice() {
+            [first] => {
+                assert_eq!(
+                    first,
+                    &Instruction::StructDeclaration {
+                        name: "Point".to_string(),
+                        fields: vec!["x".to_string(), "y".to_string()]
+                    }
+                );
+            }
+            _ => panic!("expected bytecodes to be not empty!"),
+        }
+    }
+
+    #[test]
+    fn struct_literal_bc() {
+        let yaiwr = &mut YIWR::new();
+        let ast = yaiwr.from_str("Point { y: 2, x: 1 };").unwrap();
+        let bytecode = YIWR::ast_to_bytecode(ast);
+        match bytecode.as_slice() {
+            [_, _, third] => {
+                assert_eq!(
+                    third,
+                    &Instruction::Struct {
+                        name: "Point".to_string(),
+                        fields: vec!["y".to_string(), "x".to_string()]
+                    }
+                );
+            }
+            _ => panic!("expected bytecodes to be not empty!"),
+        }
+    }
+
+    #[test]
+    fn field_access_bc() {
+        let yaiwr = &mut YIWR::new();
+        let ast = yaiwr.from_str("p.x = p.y;").unwrap();
+        let bytecode = YIWR::ast_to_bytecode(ast);
+        match bytecode.as_slice() {
+            [_, _, third, fourth] => {
+                assert_eq!(
+                    third,
+                    &Instruction::LoadField {
+                        field: "y".to_string()
+                    }
+                );
+                assert_eq!(
+                    fourth,
+                    &Instruction::StoreField {
+                        field: "x".to_string()
+                    }
+                );
+            }
+            _ => panic!("expected bytecodes to be not empty!"),
+        }
+    }
+
+    #[test]
+    fn struct_eval_keeps_declaration_order() {
+        assert_eq!(
+            YIWR::eval_input("struct Point { x, y } Point { y: 2, x: 1 };".to_string())
+                .unwrap()
+                .unwrap(),
+            EvalResult::Value(StackValue::Struct(Rc::new(RefCell::new(StructValue {
+                name: "Point".to_string(),
+                fields: vec![
+                    ("x".to_string(), StackValue::Integer(1)),
+                    ("y".to_string(), StackValue::Integer(2))
+                ]
+            }))))
+        );
+    }
+
+    #[test]
+    fn field_read_write() {
+        assert_eq!(
+            YIWR::eval_input(
+                "struct Point { x, y } let p = Point { x: 1, y: 2 }; p.x = p.x + p.y; p.x;"
+                    .to_string()
+            )
+            .unwrap()
+            .unwrap(),
+            EvalResult::Value(StackValue::Integer(3))
+        );
+    }
+
+    #[test]
+    fn structs_are_shared() {
+        let result = YIWR::eval_input(
+            "
+            struct Counter { n }
+            fun bump(c) {
+                c.n = c.n + 1;
+            }
+            let c = Counter { n: 0 };
+            bump(c);
+            bump(c);
+            c.n;
+            "
+            .to_string(),
+        );
+        assert_eq!(
+            result.unwrap().unwrap(),
+            EvalResult::Value(StackValue::Integer(2))
+        );
+    }
+
+    #[test]
+    fn nested_field_access() {
+        assert_eq!(
+            YIWR::eval_input(
+                "
+                struct Point { x, y }
+                struct Line { from, to }
+                let l = Line { from: Point { x: 0, y: 0 }, to: Point { x: 3, y: 4 } };
+                l.to.x = l.to.x * 2;
+                l.to.x + l.to.y;
+                "
+                .to_string()
+            )
+            .unwrap()
+            .unwrap(),
+            EvalResult::Value(StackValue::Integer(10))
+        );
+    }
+
+    #[test]
+    fn struct_equality() {
+        for (input, expected) in [
+            ("let a = P { x: 1 }; a == (P { x: 1.0 });", true),
+            ("let a = P { x: 1 }; a == (P { x: 2 });", false),
+            ("let a = P { x: 1 }; a == (Q { x: 1 });", false),
+        ] {
+            let input = format!("struct P {{ x }} struct Q {{ x }} {}", input);
+            assert_eq!(
+                YIWR::eval_input(input.clone()).unwrap().unwrap(),
+                EvalResult::Value(StackValue::Boolean(expected)),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn struct_quotes_strings() {
+        let input = r#"struct P { name } P { name: "a b" };"#;
+        match YIWR::eval_input(input.to_string()).unwrap() {
+            Some(EvalResult::Value(val)) => assert_eq!(val.to_string(), r#"P { name: "a b" }"#),
+            res => panic!("expected a value, got {:?}", res),
+        }
+    }
+
+    #[test]
+    fn unknown_field_err() {
+        assert_eq!(
+            YIWR::eval_input(
+                "struct Point { x, y } let p = Point { x: 1, y: 2 }; p.z;".to_string()
+            ),
+            Err(InterpError::UnknownField(
+                "Point".to_string(),
+                "z".to_string()
+            ))
+        );
+        assert_eq!(
+            YIWR::eval_input(
+                "struct Point { x, y } let p = Point { x: 1, y: 2 }; p.z = 1;".to_string()
+            ),
+            Err(InterpError::UnknownField(
+                "Point".to_string(),
+                "z".to_string()
+            ))
+        );
+        assert_eq!(
+            YIWR::eval_input("struct Point { x } Point { x: 1, z: 2 };".to_string()),
+            Err(InterpError::UnknownField(
+                "Point".to_string(),
+                "z".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn missing_field_err() {
+        assert_eq!(
+            YIWR::eval_input("struct Point { x, y } Point { x: 1 };".to_string()),
+            Err(InterpError::EvalError(
+                "Missing field 'y' in 'Point' literal".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn undefined_struct_err() {
+        assert_eq!(
+            YIWR::eval_input("Point { x: 1 };".to_string()),
+            Err(InterpError::EvalError(
+                "Undefined struct 'Point'".to_string()
+            ))
+        );
+    }
+}
@@ -0,0 +1,64 @@
+#[cfg(test)]
+mod tests {
+    use yaiwr::{
+        instruction::{BinaryOp, EvalResult, Instruction, StackValue},
+        YIWR,
+    };
+
+    #[test]
+    fn sub_bc() {
+        let yaiwr = &mut YIWR::new();
+        let ast = yaiwr.from_str("3-2;").unwrap();
+        let bytecode = YIWR::ast_to_bytecode(ast);
+        match bytecode.as_slice() {
+            [first, second, third] => {
+                assert_eq!(
+                    first,
+                    &Instruction::Push {
+                        value: StackValue::Integer(3)
+                    }
+                );
+                assert_eq!(
+                    second,
+                    &Instruction::Push {
+                        value: StackValue::Integer(2)
+                    }
+                );
+                assert_eq!(third, &Instruction::BinaryOp { op: BinaryOp::Sub });
+            }
+            _ => panic!("expected bytecodes to be not empty!"),
+        }
+    }
+
+    #[test]
+    fn eval_sub_left_associative() {
+        assert_eq!(
+            YIWR::eval_input("10-3-2;".to_string()).unwrap().unwrap(),
+            EvalResult::Value(StackValue::Integer(5)),
+            "expected 10-3-2=5"
+        );
+    }
+
+    #[test]
+    fn eval_sub_negative_result() {
+        assert_eq!(
+            YIWR::eval_input("1-2;".to_string()).unwrap().unwrap(),
+            EvalResult::Value(StackValue::Integer(-1)),
+            "expected 1-2=-1"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "underflowed")]
+    fn sub_underflow() {
+        let input = format!("{}-{};", i64::MIN + 1, 2);
+        YIWR::eval_input(input).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "overflowed")]
+    fn sub_overflow() {
+        let input = format!("{}-{};", i64::MAX, -1);
+        YIWR::eval_input(input).unwrap();
+    }
+}
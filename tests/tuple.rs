@@ -0,0 +1,169 @@
+#[cfg(test)]
+mod tests {
+    use yaiwr::{
+        ast::Pattern,
+        err::InterpError,
+        instruction::{BinaryOp, EvalResult, Instruction, StackValue},
+        YIWR,
+    };
+
+    #[test]
+    fn tuple_literal_bc() {
+        let yaiwr = &mut YIWR::new();
+        let ast = yaiwr.from_str("(1, true);").unwrap();
+        let bytecode = YIWR::ast_to_bytecode(ast);
+        match bytecode.as_slice() {
+            [first, second, third] => {
+                assert_eq!(
+                    first,
+                    &Instruction::Push {
+                        value: StackValue::Integer(1)
+                    }
+                );
+                assert_eq!(
+                    second,
+                    &Instruction::Push {
+                        value: StackValue::Boolean(true)
+                    }
+                );
+                assert_eq!(third, &Instruction::Tuple { len: 2 });
+            }
+            _ => panic!("expected bytecodes to be not empty!"),
+        }
+    }
+
+    #[test]
+    fn parenthesised_expression_is_not_a_tuple_bc() {
+        let yaiwr = &mut YIWR::new();
+        let ast = yaiwr.from_str("(1);").unwrap();
+        let bytecode = YIWR::ast_to_bytecode(ast);
+        match bytecode.as_slice() {
+            [first] => {
+                assert_eq!(
+                    first,
+                    &Instruction::Push {
+                        value: StackValue::Integer(1)
+                    }
+                );
+            }
+            _ => panic!("expected bytecodes to be not empty!"),
+        }
+    }
+
+    #[test]
+    fn destructuring_let_bc() {
+        let yaiwr = &mut YIWR::new();
+        let ast = yaiwr.from_str("let (a, (b, c)) = t;").unwrap();
+        let bytecode = YIWR::ast_to_bytecode(ast);
+        match bytecode.as_slice() {
+            [_, second] => {
+                assert_eq!(
+                    second,
+                    &Instruction::BinaryOp {
+                        op: BinaryOp::Declare {
+                            pattern: Pattern::Tuple(vec![
+                                Pattern::Id("a".to_string()),
+                                Pattern::Tuple(vec![
+                                    Pattern::Id("b".to_string()),
+                                    Pattern::Id("c".to_string())
+                                ])
+                            ])
+                        }
+                    }
+                );
+            }
+            _ => panic!("expected bytecodes to be not empty!"),
+        }
+    }
+
+    #[test]
+    fn tuple_eval() {
+        assert_eq!(
+            YIWR::eval_input("(1, (2,));".to_string()).unwrap().unwrap(),
+            EvalResult::Value(StackValue::Tuple(vec![
+                StackValue::Integer(1),
+                StackValue::Tuple(vec![StackValue::Integer(2)])
+            ]))
+        );
+    }
+
+    #[test]
+    fn tuple_quotes_strings() {
+        for (input, expected) in [(r#"("a", 1);"#, r#"("a", 1)"#), (r#"("a",);"#, r#"("a",)"#)] {
+            match YIWR::eval_input(input.to_string()).unwrap() {
+                Some(EvalResult::Value(val)) => assert_eq!(val.to_string(), expected),
+                res => panic!("expected a value, got {:?}", res),
+            }
+        }
+    }
+
+    #[test]
+    fn function_returns_multiple_values() {
+        let result = YIWR::eval_input(
+            "
+            fun div_mod(a, b) {
+                return (a / b, a % b);
+            }
+            let (q, r) = div_mod(17, 5);
+            q * 10 + r;
+            "
+            .to_string(),
+        );
+        assert_eq!(
+            result.unwrap().unwrap(),
+            EvalResult::Value(StackValue::Integer(32))
+        );
+    }
+
+    #[test]
+    fn destructuring_swap() {
+        assert_eq!(
+            YIWR::eval_input("let a = 1; let b = 2; let (a, b) = (b, a); a - b;".to_string())
+                .unwrap()
+                .unwrap(),
+            EvalResult::Value(StackValue::Integer(1))
+        );
+    }
+
+    #[test]
+    fn nested_destructuring() {
+        assert_eq!(
+            YIWR::eval_input("let (a, (b, c)) = (1, (2, 3)); a + b * c;".to_string())
+                .unwrap()
+                .unwrap(),
+            EvalResult::Value(StackValue::Integer(7))
+        );
+    }
+
+    #[test]
+    fn tuple_equality() {
+        for (input, expected) in [
+            ("(1, true) == (1.0, true);", true),
+            ("(1, 2) == (2, 1);", false),
+            ("(1, 2) != (1, 2, 3);", true),
+        ] {
+            assert_eq!(
+                YIWR::eval_input(input.to_string()).unwrap().unwrap(),
+                EvalResult::Value(StackValue::Boolean(expected)),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn destructuring_length_mismatch_err() {
+        assert_eq!(
+            YIWR::eval_input("let (a, b) = (1, 2, 3);".to_string()),
+            Err(InterpError::EvalError(
+                "Cannot destructure (1, 2, 3) into a tuple of 2 elements".to_string()
+            ))
+        );
+        assert_eq!(
+            YIWR::eval_input("let (a, b) = 1;".to_string()),
+            Err(InterpError::EvalError(
+                "Cannot destructure 1 into a tuple of 2 elements".to_string()
+            ))
+        );
+    }
+}
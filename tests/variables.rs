@@ -1,7 +1,8 @@
 #[cfg(test)]
 mod tests {
     use yaiwr::{
-        instruction::{BinaryOp, Instruction, StackValue},
+        ast::Pattern,
+        instruction::{BinaryOp, EvalResult, Instruction, StackValue},
         YIWR,
     };
 
@@ -23,7 +24,7 @@ mod tests {
                     bc2,
                     &Instruction::BinaryOp {
                         op: BinaryOp::Declare {
-                            name: "_ABCDabc123".to_string(),
+                            pattern: Pattern::Id("_ABCDabc123".to_string()),
                         }
                     }
                 );
@@ -31,4 +32,114 @@ mod tests {
             _ => panic!("expected bytecodes to be not empty!"),
         }
     }
+
+    #[test]
+    fn compound_assign_bc() {
+        let yaiwr = &mut YIWR::new();
+        let ast = yaiwr.from_str("x += 2;").unwrap();
+        let bytecode = YIWR::ast_to_bytecode(ast);
+        assert_eq!(
+            bytecode,
+            vec![
+                Instruction::Load {
+                    id: "x".to_string()
+                },
+                Instruction::Push {
+                    value: StackValue::Integer(2)
+                },
+                Instruction::BinaryOp { op: BinaryOp::Add },
+                Instruction::BinaryOp {
+                    op: BinaryOp::Assign {
+                        name: "x".to_string()
+                    }
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn compound_assign_index_bc() {
+        let yaiwr = &mut YIWR::new();
+        let ast = yaiwr.from_str("a[i] *= 2;").unwrap();
+        let bytecode = YIWR::ast_to_bytecode(ast);
+        assert_eq!(
+            bytecode,
+            vec![
+                Instruction::Load {
+                    id: "a".to_string()
+                },
+                Instruction::Load {
+                    id: "i".to_string()
+                },
+                Instruction::Dup { count: 2 },
+                Instruction::BinaryOp {
+                    op: BinaryOp::Index
+                },
+                Instruction::Push {
+                    value: StackValue::Integer(2)
+                },
+                Instruction::BinaryOp { op: BinaryOp::Mul },
+                Instruction::StoreIndex,
+            ]
+        );
+    }
+
+    #[test]
+    fn compound_assign_operators() {
+        assert_eq!(
+            YIWR::eval_input("let x = 5; x += 2; x -= 1; x *= 10; x /= 4; x %= 7; x;".to_string())
+                .unwrap()
+                .unwrap(),
+            EvalResult::Value(StackValue::Integer(1))
+        );
+    }
+
+    #[test]
+    fn compound_assign_evaluates_target_once() {
+        assert_eq!(
+            YIWR::eval_input(
+                "
+                let calls = 0;
+                let rows = [[1, 2], [3, 4]];
+                fun row() { calls += 1; return rows[1]; }
+                row()[0] += 10;
+                calls * 100 + rows[1][0];
+                "
+                .to_string()
+            )
+            .unwrap()
+            .unwrap(),
+            EvalResult::Value(StackValue::Integer(113))
+        );
+    }
+
+    #[test]
+    fn compound_assign_field_and_map() {
+        assert_eq!(
+            YIWR::eval_input(
+                "
+                struct Point { x, y }
+                let p = Point { x: 2, y: 0 };
+                p.x *= 3;
+                let m = {\"k\": 10};
+                m[\"k\"] -= p.x;
+                m[\"k\"];
+                "
+                .to_string()
+            )
+            .unwrap()
+            .unwrap(),
+            EvalResult::Value(StackValue::Integer(4))
+        );
+    }
+
+    #[test]
+    fn compound_assign_result_is_assigned_value() {
+        assert_eq!(
+            YIWR::eval_input("let x = 1; let y = 2; x += y += 3; x * 10 + y;".to_string())
+                .unwrap()
+                .unwrap(),
+            EvalResult::Value(StackValue::Integer(65))
+        );
+    }
 }